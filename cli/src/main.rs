@@ -1,5 +1,42 @@
 //use cpal::{traits::{DeviceTrait, HostTrait, StreamTrait}, SupportedStreamConfig};
-use sstv_decoder_lib::sstv_decoder::SSTVDecoder;
+use std::{
+    fs::File,
+    io::BufWriter,
+    sync::{Arc, Mutex},
+};
+
+use sstv_decoder_lib::{
+    DecodeError,
+    diagnostics::CsvDiagnostics,
+    sstv_decoder::{DecoderEvent, SSTVDecoder},
+};
+
+fn handle_events(events: Result<Vec<DecoderEvent>, DecodeError>) {
+    let events = match events {
+        Ok(events) => events,
+        Err(e) => {
+            println!("Decode failed. {}", e);
+            return;
+        }
+    };
+    for event in events {
+        match event {
+            DecoderEvent::HeaderDetected => println!("VIS header detected."),
+            DecoderEvent::VisDecoded { mode, code } => {
+                println!("VIS Mode is {}, code {}.", mode, code)
+            }
+            DecoderEvent::LineDecoded { index, .. } => println!("line {} decoded", index),
+            DecoderEvent::ImageComplete { image, .. } => {
+                image
+                    .to_rgb_image()
+                    .save("pic.png")
+                    .unwrap_or_else(|x| println!("Store failed. {:?}", x));
+                println!("Finish Decoding!");
+            }
+            DecoderEvent::Aborted(e) => println!("{}, waiting for the next header.", e),
+        }
+    }
+}
 fn main() {
     // Get the first command line argument.
     //let args: Vec<String> = std::env::args().collect();
@@ -18,18 +55,30 @@ fn main() {
     // show samples
     println!("samples.len={}", samples.len());
 
-    let mut sstv_decoder: SSTVDecoder = SSTVDecoder::new(head.sample_rate as f32);
+    let mut sstv_decoder: SSTVDecoder =
+        SSTVDecoder::new(head.sample_rate as f32).expect("unsupported sample rate");
+
+    // Frequency track for plot.gnuplot, with the tones and lines found.
+    let diagnostics = Arc::new(Mutex::new(CsvDiagnostics::new(
+        BufWriter::new(File::create("frequency.csv").expect("failed to create frequency.csv")),
+        BufWriter::new(File::create("events.csv").expect("failed to create events.csv")),
+    )));
+    sstv_decoder.set_diagnostics(diagnostics.clone());
 
     let mut cache: Vec<f32> = vec![];
     for (i, v) in samples.iter().enumerate() {
         if i % ((head.sample_rate / 10) as usize) == 0 && i != 0 {
-            sstv_decoder.decode(&cache);
+            handle_events(sstv_decoder.decode(&cache));
             cache.clear();
         }
         cache.push(*v);
     }
-    if cache.len() != 0 {
-        sstv_decoder.decode(&cache);
+    if !cache.is_empty() {
+        handle_events(sstv_decoder.decode(&cache));
+    }
+    handle_events(Ok(sstv_decoder.finish()));
+    if let Err(e) = diagnostics.lock().unwrap().flush() {
+        eprintln!("Write diagnostics failed. {:?}", e);
     }
 
     /*
@@ -46,7 +95,8 @@ fn main() {
     );
     eprintln!("Device info {:?}", &config);
 
-    let mut sstv_decoder: SSTVDecoder = SSTVDecoder::new(default_config.sample_rate().0 as f32);
+    let mut sstv_decoder: SSTVDecoder =
+        SSTVDecoder::new(default_config.sample_rate().0 as f32).expect("unsupported sample rate");
     let stream = device
         .build_input_stream(
             &config.config(),
@@ -1,6 +1,6 @@
 set datafile separator ","
 set title "瞬时频率轨迹"
-set xlabel "采样点或帧"
+set xlabel "采样点"
 set ylabel "频率 (Hz)"
-plot "whatever.csv" with lines title "Instantaneous Frequency"
+plot "frequency.csv" every ::1 using 1:2 with lines title "Instantaneous Frequency"
 pause -1
@@ -0,0 +1,60 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use thiserror::Error;
+
+use crate::{ParseModeError, sstv_image::ImageCreationError};
+
+/// Errors of the decoder.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    #[error("Invalid sample rate {0}hz. Should be above 6000hz")]
+    InvalidSampleRate(f32),
+
+    #[error("Sample {0} of the PCM data is not a finite number")]
+    InvalidSample(usize),
+
+    #[error("Parity error in VIS code {0}")]
+    VisParity(u8),
+
+    #[error("Unknown VIS code {0}")]
+    UnknownVisCode(u8),
+
+    #[error("The picture is aborted")]
+    Aborted,
+}
+
+/// Errors of the encoder.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum EncodeError {
+    #[error("Invalid sample rate {0}hz. Should be above 4600hz")]
+    InvalidSampleRate(u32),
+
+    #[error("Invalid peak amplitude {0}dBFS. Should not be above 0dBFS")]
+    InvalidAmplitude(f32),
+
+    #[error("The image is empty")]
+    EmptyImage,
+
+    #[error("Create output file error: {0}")]
+    CreateFileError(String),
+
+    #[error("Write output file error: {0}")]
+    WriteFileError(String),
+}
+
+/// Any error of the SSTV libraries.
+#[derive(Error, Debug)]
+pub enum SstvError {
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+
+    #[error(transparent)]
+    Encode(#[from] EncodeError),
+
+    #[error(transparent)]
+    ImageCreation(#[from] ImageCreationError),
+
+    #[error(transparent)]
+    ParseMode(#[from] ParseModeError),
+}
@@ -0,0 +1,18 @@
+//! # sstv-core
+//!
+//! sstv-core holds what the SSTV encoder and decoder share: the specification of
+//! every supported mode, the image type, and the pcm sample formats.
+//!
+//! Adding a mode is one entry of [`MODE_SPECS`], as long as it follows the line layout
+//! of one of the [`Family`] values.
+
+mod error;
+mod mode;
+mod sample;
+pub mod sstv_image;
+
+pub use error::{DecodeError, EncodeError, SstvError};
+pub use mode::{
+    Channel, ColorModel, Family, MODE_SPECS, Mode, ModeSpec, ParseModeError, nearest_sample,
+};
+pub use sample::{I24, Sample};
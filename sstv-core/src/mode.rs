@@ -0,0 +1,578 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use thiserror::Error;
+
+/// Support modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    Scottie1,
+    Scottie2,
+    Scottie3,
+    Scottie4,
+    ScottieDx,
+    Martin1,
+    Martin2,
+    Martin3,
+    Martin4,
+    Robot24,
+    Robot36,
+    Robot72,
+    Robot8Bw,
+    Robot12Bw,
+    Robot24Bw,
+    Robot36Bw,
+    WrasseSc2_30,
+    WrasseSc2_60,
+    WrasseSc2_120,
+    WrasseSc2_180,
+    P3,
+    P5,
+    P7,
+    Pd50,
+    Pd90,
+    Pd120,
+    Pd160,
+    Pd180,
+    Pd240,
+    Pd290,
+}
+
+/// How the colour of a pixel is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorModel {
+    /// Red, green and blue scans.
+    Rgb,
+    /// Y, R-Y and B-Y scans.
+    ///
+    /// Refrence: Dayton Paper Appendix B: YRyBy (YCrCb) Color Encoding
+    YCrCb,
+    /// Only the brightness, as a black and white picture.
+    Monochrome,
+}
+
+/// A scan of one colour component over a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+    Y,
+    RY,
+    BY,
+}
+
+impl Channel {
+    /// The index of the channel inside a [R, G, B] or a [Y, R-Y, B-Y] pixel.
+    pub fn index(&self) -> usize {
+        match self {
+            Channel::Red | Channel::Y => 0,
+            Channel::Green | Channel::RY => 1,
+            Channel::Blue | Channel::BY => 2,
+        }
+    }
+}
+
+/// The line layout of a mode, where the sync pulse, the porches and the separators are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    /// Separator, scan, separator, scan, sync, porch, scan.
+    ///
+    /// A "starting" sync pulse is sent once before the first line.
+    Scottie,
+    /// Sync, porch, scan, separator, scan, separator, scan, separator.
+    Martin,
+    /// Sync, porch, Y, separator, separator porch, chroma.
+    ///
+    /// The chroma scans take half the time of the Y scan. When the scan order has only
+    /// one chroma after the Y, R-Y and B-Y are sent on alternate lines; otherwise both
+    /// follow the Y, each after its own separator.
+    ///
+    /// The black and white modes send only the Y scan: sync, porch, Y.
+    Robot,
+    /// Sync, porch, scan, scan, scan.
+    Wraase,
+    /// Sync, porch, scan, porch, scan, porch, scan, porch.
+    Pasokon,
+    /// Sync, porch, Y of the first line, R-Y, B-Y, Y of the second line.
+    ///
+    /// Two lines of the picture are sent after each sync pulse, sharing the chroma.
+    Pd,
+}
+
+/// Everything about a mode, used by both the encoder and the decoder.
+///
+/// All the times are in ms, in f64 so that the pixels of a long mode add up without
+/// drifting away from the nominal timing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeSpec {
+    pub mode: Mode,
+    /// Name of the mode, as in "Martin 1" or "PD120".
+    pub name: &'static str,
+    pub vis_code: u8,
+    pub width: usize,
+    pub height: usize,
+    pub color_model: ColorModel,
+    pub family: Family,
+    /// The channels in the order they are sent inside a line.
+    pub scan_order: &'static [Channel],
+    /// 1200hz sync pulse.
+    pub sync_time: f64,
+    /// 1500hz porch after the sync pulse.
+    pub porch_time: f64,
+    /// Separator pulse between the scans, zero if there is none.
+    pub separator_time: f64,
+    /// Porch after the separator pulse, zero if there is none.
+    pub separator_porch_time: f64,
+    /// Scan time of one pixel. For Robot modes, this is the Y scan.
+    pub pixel_time: f64,
+}
+
+/// Every supported mode.
+///
+/// Refrence: Dayton Paper, and the SSTV Handbook for the PD modes.
+pub const MODE_SPECS: [ModeSpec; 30] = [
+    scottie(Mode::Scottie1, "Scottie 1", 60, 256, 0.4320),
+    scottie(Mode::Scottie2, "Scottie 2", 56, 256, 0.2752),
+    scottie(Mode::Scottie3, "Scottie 3", 52, 128, 0.4320),
+    scottie(Mode::Scottie4, "Scottie 4", 48, 128, 0.2752),
+    scottie(Mode::ScottieDx, "Scottie DX", 76, 256, 1.08),
+    martin(Mode::Martin1, "Martin 1", 44, 256, 0.4576),
+    martin(Mode::Martin2, "Martin 2", 40, 256, 0.2288),
+    martin(Mode::Martin3, "Martin 3", 36, 128, 0.4576),
+    martin(Mode::Martin4, "Martin 4", 32, 128, 0.2288),
+    ModeSpec {
+        mode: Mode::Robot24,
+        name: "Robot 24",
+        vis_code: 4,
+        width: 160,
+        height: 120,
+        color_model: ColorModel::YCrCb,
+        family: Family::Robot,
+        scan_order: &[Channel::Y, Channel::RY, Channel::BY],
+        sync_time: 9.0,
+        porch_time: 3.0,
+        separator_time: 4.5,
+        separator_porch_time: 1.5,
+        pixel_time: 88.0 / 160.0,
+    },
+    ModeSpec {
+        mode: Mode::Robot36,
+        name: "Robot 36",
+        vis_code: 8,
+        width: 320,
+        height: 240,
+        color_model: ColorModel::YCrCb,
+        family: Family::Robot,
+        scan_order: &[Channel::Y, Channel::RY],
+        sync_time: 9.0,
+        porch_time: 3.0,
+        separator_time: 4.5,
+        separator_porch_time: 1.5,
+        pixel_time: 88.0 / 320.0,
+    },
+    ModeSpec {
+        mode: Mode::Robot72,
+        name: "Robot 72",
+        vis_code: 12,
+        width: 320,
+        height: 240,
+        color_model: ColorModel::YCrCb,
+        family: Family::Robot,
+        scan_order: &[Channel::Y, Channel::RY, Channel::BY],
+        sync_time: 9.0,
+        porch_time: 3.0,
+        separator_time: 4.5,
+        separator_porch_time: 1.5,
+        pixel_time: 138.0 / 320.0,
+    },
+    robot_bw(Mode::Robot8Bw, "Robot 8 B/W", 2, 160, 120, 58.89709),
+    robot_bw(Mode::Robot12Bw, "Robot 12 B/W", 6, 160, 120, 92.0),
+    robot_bw(Mode::Robot24Bw, "Robot 24 B/W", 10, 320, 240, 92.0),
+    robot_bw(Mode::Robot36Bw, "Robot 36 B/W", 14, 320, 240, 142.0),
+    wraase(Mode::WrasseSc2_30, "Wraase SC2-30", 51, 128, 0.2441),
+    wraase(Mode::WrasseSc2_60, "Wraase SC2-60", 59, 256, 0.2441),
+    wraase(Mode::WrasseSc2_120, "Wraase SC2-120", 63, 256, 0.4890),
+    wraase(Mode::WrasseSc2_180, "Wraase SC2-180", 55, 256, 0.7344),
+    pasokon(Mode::P3, "P3", 113, 5.208, 1.042, 0.2083),
+    pasokon(Mode::P5, "P5", 114, 7.813, 1.563, 0.3125),
+    pasokon(Mode::P7, "P7", 115, 10.417, 2.083, 0.4167),
+    pd(Mode::Pd50, "PD50", 93, 320, 256, 91.52),
+    pd(Mode::Pd90, "PD90", 99, 320, 256, 170.24),
+    pd(Mode::Pd120, "PD120", 95, 640, 496, 121.6),
+    pd(Mode::Pd160, "PD160", 98, 512, 400, 195.584),
+    pd(Mode::Pd180, "PD180", 96, 640, 496, 183.04),
+    pd(Mode::Pd240, "PD240", 97, 640, 496, 244.48),
+    pd(Mode::Pd290, "PD290", 94, 800, 616, 228.8),
+];
+
+const fn scottie(
+    mode: Mode,
+    name: &'static str,
+    vis_code: u8,
+    height: usize,
+    pixel_time: f64,
+) -> ModeSpec {
+    ModeSpec {
+        mode,
+        name,
+        vis_code,
+        width: 320,
+        height,
+        color_model: ColorModel::Rgb,
+        family: Family::Scottie,
+        scan_order: &[Channel::Green, Channel::Blue, Channel::Red],
+        sync_time: 9.0,
+        porch_time: 1.5,
+        separator_time: 1.5,
+        separator_porch_time: 0.0,
+        pixel_time,
+    }
+}
+
+const fn martin(
+    mode: Mode,
+    name: &'static str,
+    vis_code: u8,
+    height: usize,
+    pixel_time: f64,
+) -> ModeSpec {
+    ModeSpec {
+        mode,
+        name,
+        vis_code,
+        width: 320,
+        height,
+        color_model: ColorModel::Rgb,
+        family: Family::Martin,
+        scan_order: &[Channel::Green, Channel::Blue, Channel::Red],
+        sync_time: 4.862,
+        porch_time: 0.572,
+        separator_time: 0.572,
+        separator_porch_time: 0.0,
+        pixel_time,
+    }
+}
+
+/// Robot black and white modes are given with the scan time of a whole line.
+const fn robot_bw(
+    mode: Mode,
+    name: &'static str,
+    vis_code: u8,
+    width: usize,
+    height: usize,
+    scan_time: f64,
+) -> ModeSpec {
+    ModeSpec {
+        mode,
+        name,
+        vis_code,
+        width,
+        height,
+        color_model: ColorModel::Monochrome,
+        family: Family::Robot,
+        scan_order: &[Channel::Y],
+        sync_time: 7.0,
+        porch_time: 1.0,
+        separator_time: 0.0,
+        separator_porch_time: 0.0,
+        pixel_time: scan_time / width as f64,
+    }
+}
+
+/// Wraase SC2 modes only. The older SC1 modes are not supported, since none of the
+/// references of this table gives their VIS codes or their line timing.
+const fn wraase(
+    mode: Mode,
+    name: &'static str,
+    vis_code: u8,
+    height: usize,
+    pixel_time: f64,
+) -> ModeSpec {
+    ModeSpec {
+        mode,
+        name,
+        vis_code,
+        width: 320,
+        height,
+        color_model: ColorModel::Rgb,
+        family: Family::Wraase,
+        scan_order: &[Channel::Red, Channel::Green, Channel::Blue],
+        sync_time: 5.5225,
+        porch_time: 0.5,
+        separator_time: 0.0,
+        separator_porch_time: 0.0,
+        pixel_time,
+    }
+}
+
+const fn pasokon(
+    mode: Mode,
+    name: &'static str,
+    vis_code: u8,
+    sync_time: f64,
+    porch_time: f64,
+    pixel_time: f64,
+) -> ModeSpec {
+    ModeSpec {
+        mode,
+        name,
+        vis_code,
+        width: 640,
+        height: 496,
+        color_model: ColorModel::Rgb,
+        family: Family::Pasokon,
+        scan_order: &[Channel::Red, Channel::Green, Channel::Blue],
+        sync_time,
+        porch_time,
+        separator_time: porch_time,
+        separator_porch_time: 0.0,
+        pixel_time,
+    }
+}
+
+/// PD modes are given with the scan time of a whole line.
+const fn pd(
+    mode: Mode,
+    name: &'static str,
+    vis_code: u8,
+    width: usize,
+    height: usize,
+    scan_time: f64,
+) -> ModeSpec {
+    ModeSpec {
+        mode,
+        name,
+        vis_code,
+        width,
+        height,
+        color_model: ColorModel::YCrCb,
+        family: Family::Pd,
+        scan_order: &[Channel::Y, Channel::RY, Channel::BY, Channel::Y],
+        sync_time: 20.0,
+        porch_time: 2.08,
+        separator_time: 0.0,
+        separator_porch_time: 0.0,
+        pixel_time: scan_time / width as f64,
+    }
+}
+
+/// Leader tone, break, leader tone and the ten VIS bits, in ms.
+///
+/// Refrence: Dayton Paper - VIS Code and Robot calibration header
+const HEADER_TIME: f64 = 300.0 + 10.0 + 300.0 + 30.0 * 10.0;
+
+/// How far before a half sample a time is still rounded up, in ms.
+///
+/// The encoder adds up the pixel times one by one, which ends a few ns away from the
+/// times of the mode table after a long mode. A time halfway between two samples would
+/// then be rounded either way.
+const HALF_SAMPLE_TOLERANCE: f64 = 1e-5;
+
+/// The sample nearest to the time in ms since the start of the transmission, a time
+/// halfway between two samples goes to the later one.
+///
+/// The encoder ends every tone at this sample, so the sample count of a mode is exact.
+pub fn nearest_sample(time_in_ms: f64, sample_rate: u32) -> u64 {
+    ((time_in_ms + HALF_SAMPLE_TOLERANCE) * sample_rate as f64 / 1000.0).round() as u64
+}
+
+/// The name given to [`Mode::from_str`] is not a mode.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ParseModeError {
+    #[error("Unknown mode {0}")]
+    UnknownMode(String),
+}
+
+impl Mode {
+    /// The specification of the mode.
+    pub fn spec(&self) -> &'static ModeSpec {
+        MODE_SPECS
+            .iter()
+            .find(|spec| spec.mode == *self)
+            .expect("every mode has a specification")
+    }
+
+    /// Every supported mode, in the order of [`MODE_SPECS`].
+    pub fn all() -> impl Iterator<Item = Mode> {
+        MODE_SPECS.iter().map(|spec| spec.mode)
+    }
+
+    /// Find the mode of the VIS code.
+    pub fn from_vis_code(vis_code: u8) -> Option<Mode> {
+        ModeSpec::from_vis_code(vis_code).map(|spec| spec.mode)
+    }
+
+    /// Find the mode by its name, see [`Mode::from_str`].
+    pub fn from_name(name: &str) -> Option<Mode> {
+        let name = normalize_name(name);
+        MODE_SPECS
+            .iter()
+            .find(|spec| {
+                normalize_name(spec.name) == name
+                    || normalize_name(&format!("{:?}", spec.mode)) == name
+            })
+            .map(|spec| spec.mode)
+    }
+
+    /// Name of the mode, as in "Martin 1" or "PD120".
+    pub fn name(&self) -> &'static str {
+        self.spec().name
+    }
+
+    /// Time to send a picture, including the VIS header.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.spec().transmission_time(true) / 1000.0)
+    }
+
+    /// Number of samples to send a picture at the sample rate, including the VIS header.
+    ///
+    /// The end of the transmission is the [`nearest_sample`] to its time, as the end of
+    /// every tone of the encoder.
+    pub fn sample_count(&self, sample_rate: u32) -> usize {
+        nearest_sample(self.spec().transmission_time(true), sample_rate) as usize
+    }
+}
+
+/// Only the letters and digits matter, regardless of the case.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parse the name of a mode.
+///
+/// Spaces, dashes and the case are ignored, so "Martin 1", "martin1" and "MARTIN-1"
+/// are all Martin 1. The variant names like "WrasseSc2_180" work as well.
+impl FromStr for Mode {
+    type Err = ParseModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mode::from_name(s).ok_or_else(|| ParseModeError::UnknownMode(s.to_string()))
+    }
+}
+
+impl ModeSpec {
+    /// Find the mode of the VIS code.
+    pub fn from_vis_code(vis_code: u8) -> Option<&'static ModeSpec> {
+        MODE_SPECS.iter().find(|spec| spec.vis_code == vis_code)
+    }
+
+    /// Scan time of a whole line of the channel.
+    ///
+    /// Robot modes scan the chroma in half the time of the Y.
+    pub fn scan_time(&self, channel: Channel) -> f64 {
+        let scan_time = self.pixel_time * self.width as f64;
+        match (self.family, channel) {
+            (Family::Robot, Channel::RY | Channel::BY) => scan_time / 2.0,
+            _ => scan_time,
+        }
+    }
+
+    /// Time between two sync pulses.
+    ///
+    /// A Scottie line goes from the separator before the green scan to the end of the
+    /// red scan, without the "starting" sync pulse. A PD line carries two lines of the
+    /// picture.
+    pub fn line_time(&self) -> f64 {
+        let scan_time = self.scan_time(self.scan_order[0]);
+        match self.family {
+            Family::Scottie => {
+                self.separator_time * 2.0 + self.sync_time + self.porch_time + scan_time * 3.0
+            }
+            Family::Martin | Family::Pasokon => {
+                self.sync_time + self.porch_time + self.separator_time * 3.0 + scan_time * 3.0
+            }
+            Family::Robot => {
+                let chroma_count = self.scan_order.len() - 1;
+                self.sync_time
+                    + self.porch_time
+                    + scan_time
+                    + (self.separator_time
+                        + self.separator_porch_time
+                        + self.scan_time(Channel::RY))
+                        * chroma_count as f64
+            }
+            Family::Wraase => self.sync_time + self.porch_time + scan_time * 3.0,
+            Family::Pd => self.sync_time + self.porch_time + scan_time * 4.0,
+        }
+    }
+
+    /// Number of lines sent, which is half of the height for PD modes.
+    pub fn line_count(&self) -> usize {
+        match self.family {
+            Family::Pd => self.height / 2,
+            _ => self.height,
+        }
+    }
+
+    /// Time to send a picture in ms, with or without the VIS header.
+    pub fn transmission_time(&self, vis_header: bool) -> f64 {
+        let header_time = if vis_header { HEADER_TIME } else { 0.0 };
+        let starting_sync = match self.family {
+            Family::Scottie => self.sync_time,
+            _ => 0.0,
+        };
+        header_time + starting_sync + self.line_time() * self.line_count() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_names() {
+        assert_eq!("pd120".parse(), Ok(Mode::Pd120));
+        assert_eq!("Martin 1".parse(), Ok(Mode::Martin1));
+        assert_eq!("MARTIN-1".parse(), Ok(Mode::Martin1));
+        assert_eq!("WrasseSc2_180".parse(), Ok(Mode::WrasseSc2_180));
+        assert_eq!(
+            "Martin 9".parse::<Mode>(),
+            Err(ParseModeError::UnknownMode("Martin 9".to_string()))
+        );
+    }
+
+    #[test]
+    fn display_round_trips_through_from_str() {
+        for mode in Mode::all() {
+            assert_eq!(mode.to_string().parse(), Ok(mode));
+        }
+    }
+
+    #[test]
+    fn vis_codes_are_unique() {
+        for spec in &MODE_SPECS {
+            assert_eq!(Mode::from_vis_code(spec.vis_code), Some(spec.mode));
+            assert_eq!(ModeSpec::from_vis_code(spec.vis_code), Some(spec));
+        }
+        assert_eq!(Mode::from_vis_code(127), None);
+    }
+
+    #[test]
+    fn sample_count_of_robot36() {
+        // 910ms of VIS header, then 240 lines of 150ms.
+        assert_eq!(Mode::Robot36.duration().as_millis(), 36910);
+        // 36910ms is 406932.75 samples at 11025hz.
+        assert_eq!(Mode::Robot36.sample_count(11025), 406933);
+        assert_eq!(Mode::Robot36.sample_count(48000), 1771680);
+    }
+
+    #[test]
+    fn sample_count_rounds_to_the_nearest_sample() {
+        // 110543.32ms and 12910ms, 884346.56 and 103280 samples at 8000hz.
+        assert_eq!(Mode::Scottie1.sample_count(8000), 884347);
+        assert_eq!(Mode::Robot12Bw.sample_count(8000), 103280);
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+///
+/// A pcm sample format.
+///
+/// Every format converts from and to a float in [-1, 1], where 0 is the silence.
+/// Integers map [`Sample::to_f32`] by dividing with 2^(bits - 1), the same way most
+/// audio libraries do, and [`Sample::from_f32`] rounds and clamps the other way.
+///
+pub trait Sample: Copy + Send + Sync + 'static {
+    /// Bits of one sample inside a WAV file.
+    const BITS_PER_SAMPLE: u16;
+
+    /// Whether the WAV file stores the sample as IEEE float.
+    const IS_FLOAT: bool;
+
+    /// Convert from a float in [-1, 1], values outside are clamped.
+    fn from_f32(value: f32) -> Self;
+
+    /// Convert into a float in [-1, 1].
+    fn to_f32(self) -> f32;
+
+    /// Append the little endian bytes of the sample, as a WAV file stores it.
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>);
+
+    /// Append the big endian bytes of the sample, as a Sun .au file stores it.
+    fn extend_be_bytes(self, bytes: &mut Vec<u8>);
+}
+
+/// Scale the float into an integer between min and max, rounding half away from zero.
+///
+/// f32::round is a call into libm on most targets, so it rounds by the integer cast
+/// instead. The bounds are integers, so clamping before rounding gives the same result,
+/// and a NaN turns into min.
+#[inline]
+fn scale(value: f32, full_scale: f32, min: f32, max: f32) -> i32 {
+    let value = (value * full_scale).max(min).min(max);
+    let truncated = value as i32;
+    // Twice the fraction truncates to 1 or -1 only from a half on.
+    truncated + ((value - truncated as f32) * 2.0) as i32
+}
+
+impl Sample for f32 {
+    const BITS_PER_SAMPLE: u16 = 32;
+    const IS_FLOAT: bool = true;
+
+    #[inline]
+    fn from_f32(value: f32) -> Self {
+        value.clamp(-1.0, 1.0)
+    }
+
+    #[inline]
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    #[inline]
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    #[inline]
+    fn extend_be_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Sample for i32 {
+    const BITS_PER_SAMPLE: u16 = 32;
+    const IS_FLOAT: bool = false;
+
+    #[inline]
+    fn from_f32(value: f32) -> Self {
+        // f32 can not hold i32::MAX, clamp in f64 instead.
+        (value as f64 * 2147483648.0)
+            .round()
+            .clamp(i32::MIN as f64, i32::MAX as f64) as i32
+    }
+
+    #[inline]
+    fn to_f32(self) -> f32 {
+        (self as f64 / 2147483648.0) as f32
+    }
+
+    #[inline]
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    #[inline]
+    fn extend_be_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Sample for I24 {
+    const BITS_PER_SAMPLE: u16 = 24;
+    const IS_FLOAT: bool = false;
+
+    #[inline]
+    fn from_f32(value: f32) -> Self {
+        Self(scale(
+            value,
+            8388608.0,
+            I24::MIN.0 as f32,
+            I24::MAX.0 as f32,
+        ))
+    }
+
+    #[inline]
+    fn to_f32(self) -> f32 {
+        self.0 as f32 / 8388608.0
+    }
+
+    #[inline]
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.0.to_le_bytes()[..3]);
+    }
+
+    #[inline]
+    fn extend_be_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.0.to_be_bytes()[1..]);
+    }
+}
+
+impl Sample for i16 {
+    const BITS_PER_SAMPLE: u16 = 16;
+    const IS_FLOAT: bool = false;
+
+    #[inline]
+    fn from_f32(value: f32) -> Self {
+        scale(value, 32768.0, i16::MIN as f32, i16::MAX as f32) as i16
+    }
+
+    #[inline]
+    fn to_f32(self) -> f32 {
+        self as f32 / 32768.0
+    }
+
+    #[inline]
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+
+    #[inline]
+    fn extend_be_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_be_bytes());
+    }
+}
+
+impl Sample for u8 {
+    const BITS_PER_SAMPLE: u16 = 8;
+    const IS_FLOAT: bool = false;
+
+    /// The silence is 128, as 8 bit WAV files store it.
+    #[inline]
+    fn from_f32(value: f32) -> Self {
+        (scale(value, 128.0, -128.0, 127.0) + 128) as u8
+    }
+
+    #[inline]
+    fn to_f32(self) -> f32 {
+        (self as f32 - 128.0) / 128.0
+    }
+
+    #[inline]
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.push(self);
+    }
+
+    /// Sun .au files only have signed 8 bit samples.
+    #[inline]
+    fn extend_be_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.push(self ^ 0x80);
+    }
+}
+
+///
+/// A 24 bit signed sample, stored in the lower bits of an i32.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct I24(i32);
+
+impl I24 {
+    pub const MIN: I24 = I24(-8388608);
+    pub const MAX: I24 = I24(8388607);
+
+    /// Return None if the value does not fit in 24 bits.
+    pub fn new(value: i32) -> Option<Self> {
+        (Self::MIN.0..=Self::MAX.0)
+            .contains(&value)
+            .then_some(Self(value))
+    }
+
+    pub fn get(self) -> i32 {
+        self.0
+    }
+}
+
+impl From<I24> for i32 {
+    fn from(value: I24) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn le_bytes<S: Sample>(sample: S) -> Vec<u8> {
+        let mut bytes = vec![];
+        sample.extend_le_bytes(&mut bytes);
+        bytes
+    }
+
+    fn be_bytes<S: Sample>(sample: S) -> Vec<u8> {
+        let mut bytes = vec![];
+        sample.extend_be_bytes(&mut bytes);
+        bytes
+    }
+
+    #[test]
+    fn u8_silence_is_128() {
+        assert_eq!(u8::from_f32(0.0), 128);
+        assert_eq!(u8::from_f32(1.0), 255);
+        assert_eq!(u8::from_f32(-1.0), 0);
+        assert_eq!(128u8.to_f32(), 0.0);
+        assert_eq!(le_bytes(200u8), [200]);
+        // Signed inside a .au file.
+        assert_eq!(be_bytes(200u8), [72]);
+        assert_eq!(be_bytes(u8::from_f32(0.0)), [0]);
+    }
+
+    #[test]
+    fn i16_full_scale() {
+        assert_eq!(i16::from_f32(1.0), 32767);
+        assert_eq!(i16::from_f32(-1.0), -32768);
+        assert_eq!(i16::from_f32(2.0), 32767);
+        assert_eq!(i16::from_f32(0.5), 16384);
+        assert_eq!(le_bytes(0x1234i16), [0x34, 0x12]);
+        assert_eq!(be_bytes(0x1234i16), [0x12, 0x34]);
+    }
+
+    #[test]
+    fn i24_byte_order() {
+        let sample = I24::new(0x123456).unwrap();
+        assert_eq!(le_bytes(sample), [0x56, 0x34, 0x12]);
+        assert_eq!(be_bytes(sample), [0x12, 0x34, 0x56]);
+        assert_eq!(le_bytes(I24::from_f32(-1.0)), [0x00, 0x00, 0x80]);
+        assert_eq!(be_bytes(I24::from_f32(-1.0)), [0x80, 0x00, 0x00]);
+        assert_eq!(I24::from_f32(1.0), I24::MAX);
+        assert_eq!(I24::new(I24::MAX.get() + 1), None);
+    }
+
+    #[test]
+    fn i32_extremes() {
+        assert_eq!(i32::from_f32(1.0), i32::MAX);
+        assert_eq!(i32::from_f32(-1.0), i32::MIN);
+        assert_eq!(i32::from_f32(0.0), 0);
+        assert_eq!(i32::MIN.to_f32(), -1.0);
+    }
+
+    #[test]
+    fn to_f32_round_trips() {
+        for value in [-1.0, -0.5, -0.25, 0.0, 0.25, 0.5] {
+            assert_eq!(u8::from_f32(value).to_f32(), value);
+            assert_eq!(i16::from_f32(value).to_f32(), value);
+            assert_eq!(I24::from_f32(value).to_f32(), value);
+            assert_eq!(i32::from_f32(value).to_f32(), value);
+            assert_eq!(f32::from_f32(value).to_f32(), value);
+        }
+        for sample in [i16::MIN, -1, 0, 1, i16::MAX] {
+            assert_eq!(i16::from_f32(sample.to_f32()), sample);
+        }
+    }
+}
@@ -11,6 +11,7 @@ pub enum ImageCreationError {
 
 /// The image structure is just a one-demension vector of (r,g,b) values.
 /// With width and height info.
+#[derive(Debug, Clone, PartialEq)]
 pub struct SSTVImage {
     width: usize,
     height: usize,
@@ -44,16 +45,20 @@ impl SSTVImage {
         let mut i: usize = 0;
         let mut result: Vec<[u8; 3]> = vec![];
         while i < rgb_image.len() {
-            result.push([
-                rgb_image[i].into(),
-                rgb_image[i + 1].into(),
-                rgb_image[i + 2].into(),
-            ]);
+            result.push([rgb_image[i], rgb_image[i + 1], rgb_image[i + 2]]);
             i += 3;
         }
         Self::new(image.width() as usize, image.height() as usize, result)
     }
 
+    /// Convert into RgbImage, image feature should be enabled first.
+    #[cfg(feature = "image")]
+    pub fn to_rgb_image(&self) -> image::RgbImage {
+        image::RgbImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            image::Rgb(self.get_rgb_pixel(x as usize, y as usize))
+        })
+    }
+
     /// Get the height of the image.
     pub fn get_height(&self) -> usize {
         self.height
@@ -69,6 +74,15 @@ impl SSTVImage {
         self.image[y * self.width + x]
     }
 
+    /// Get the brightness of the pixel at the (x,y) position, 0 as black and 255 as white.
+    ///
+    /// Unlike the Y of [`SSTVImage::get_ycrcb_pixel`], it uses the whole range, since the
+    /// black and white modes send it alone.
+    pub fn get_luminance(&self, x: usize, y: usize) -> u8 {
+        let [r, g, b] = self.get_rgb_pixel(x, y).map(f32::from);
+        (0.299 * r + 0.587 * g + 0.114 * b).round() as u8
+    }
+
     /// Get the pixel at the (x,y) position, with [Y, Ry, By] values.
     ///
     /// Refrence is at below:
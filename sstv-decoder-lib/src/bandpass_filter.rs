@@ -1,17 +1,46 @@
 use biquad::{Biquad, Coefficients, DirectForm1, ToHertz, Type};
+use sstv_core::DecodeError;
 
-pub fn bandpass_filter(samples: &[f32], fs: f32) -> Vec<f32> {
-    let fl = 1.khz();
-    let fh = 3.khz();
-    let fs = fs.hz();
+/// 1000hz to 3000hz bandpass filter, which keeps its state between the chunks.
+pub struct BandpassFilter {
+    biquad_lp: DirectForm1<f32>,
+    biquad_hp: DirectForm1<f32>,
+}
+
+impl BandpassFilter {
+    /// Will return an error if the sample rate could not carry 3000hz.
+    pub fn new(sample_rate: f32) -> Result<Self, DecodeError> {
+        let error = DecodeError::InvalidSampleRate(sample_rate);
+        // 3000hz has to be below the Nyquist frequency. This also keeps out the sample
+        // rates which are not positive, which would panic inside `hz()`.
+        if !sample_rate.is_finite() || sample_rate <= 6000.0 {
+            return Err(error);
+        }
+        let fl = 1.khz();
+        let fh = 3.khz();
+        let fs = sample_rate.hz();
+
+        let coeffs_lp = Coefficients::<f32>::from_params(Type::LowPass, fs, fh, 1.)
+            .map_err(|_| error.clone())?;
+        let coeffs_hp =
+            Coefficients::<f32>::from_params(Type::HighPass, fs, fl, 1.).map_err(|_| error)?;
+
+        Ok(Self {
+            biquad_lp: DirectForm1::<f32>::new(coeffs_lp),
+            biquad_hp: DirectForm1::<f32>::new(coeffs_hp),
+        })
+    }
 
-    let coeffs_lp = Coefficients::<f32>::from_params(Type::LowPass, fs, fh, 1.).unwrap();
-    let coeffs_hp = Coefficients::<f32>::from_params(Type::HighPass, fs, fl, 1.).unwrap();
+    /// Forget the previous samples.
+    pub fn reset(&mut self) {
+        self.biquad_lp.reset_state();
+        self.biquad_hp.reset_state();
+    }
 
-    let mut biquad_lp = DirectForm1::<f32>::new(coeffs_lp);
-    let mut biquad_hp = DirectForm1::<f32>::new(coeffs_hp);
-    samples
-        .iter()
-        .map(|&x| biquad_lp.run(biquad_hp.run(x)))
-        .collect()
+    pub fn run(&mut self, samples: &[f32]) -> Vec<f32> {
+        samples
+            .iter()
+            .map(|&x| self.biquad_lp.run(self.biquad_hp.run(x)))
+            .collect()
+    }
 }
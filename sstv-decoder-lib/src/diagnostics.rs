@@ -0,0 +1,184 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+/// A tone measured by the decoder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tone {
+    /// 1900hz leader tone of the VIS header.
+    Leader,
+    /// 1200hz break between the leader tones.
+    Break,
+    /// A bit of the VIS code, from 0 (start bit) to 9 (stop bit).
+    VisBit(usize),
+    /// Robot 36 separator pulse, which tells the chroma of the line.
+    Separator,
+    /// 1200hz sync pulse of a line, measured for the calibration.
+    Sync,
+    /// 1500hz pulse of a line, measured for the calibration.
+    Black,
+}
+
+/// Where a tone is found in the stream, and its average frequency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedTone {
+    pub tone: Tone,
+    /// Index of the first sample since the decoder started.
+    pub position: usize,
+    /// Length in samples.
+    pub length: usize,
+    /// Average frequency in hz.
+    pub frequency: f32,
+}
+
+/// Where a line of the picture is found in the stream.
+///
+/// PD modes send two lines in one sync period, which is reported once with the
+/// index of the first line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineTiming {
+    pub index: usize,
+    /// Index of the first sample since the decoder started, including the sync pulse.
+    pub position: usize,
+    /// Length in samples.
+    pub length: usize,
+}
+
+/// Receive the internal data of the decoder, for debugging and plotting.
+///
+/// Every method does nothing by default, implement the ones you need.
+pub trait Diagnostics {
+    /// The instantaneous frequency track in hz, `position` is the index of the first one.
+    fn frequency(&mut self, _position: usize, _frequencies: &[f32]) {}
+
+    /// A tone of the VIS header or a line is measured.
+    fn tone(&mut self, _tone: &DetectedTone) {}
+
+    /// A line of the picture is going to be decoded.
+    fn line(&mut self, _line: &LineTiming) {}
+}
+
+/// Share the diagnostics with the decoder, and read it afterwards.
+impl<D: Diagnostics + ?Sized> Diagnostics for Arc<Mutex<D>> {
+    fn frequency(&mut self, position: usize, frequencies: &[f32]) {
+        if let Ok(mut diagnostics) = self.lock() {
+            diagnostics.frequency(position, frequencies);
+        }
+    }
+
+    fn tone(&mut self, tone: &DetectedTone) {
+        if let Ok(mut diagnostics) = self.lock() {
+            diagnostics.tone(tone);
+        }
+    }
+
+    fn line(&mut self, line: &LineTiming) {
+        if let Ok(mut diagnostics) = self.lock() {
+            diagnostics.line(line);
+        }
+    }
+}
+
+/// Keep everything in memory.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryDiagnostics {
+    pub frequencies: Vec<f32>,
+    pub tones: Vec<DetectedTone>,
+    pub lines: Vec<LineTiming>,
+}
+
+impl Diagnostics for MemoryDiagnostics {
+    fn frequency(&mut self, _position: usize, frequencies: &[f32]) {
+        self.frequencies.extend_from_slice(frequencies);
+    }
+
+    fn tone(&mut self, tone: &DetectedTone) {
+        self.tones.push(tone.clone());
+    }
+
+    fn line(&mut self, line: &LineTiming) {
+        self.lines.push(line.clone());
+    }
+}
+
+/// Write the frequency track and the tones and lines into two CSV files.
+///
+/// The frequency track has `sample,frequency` rows, which could be plotted with
+/// `plot.gnuplot`. The tones and lines have `sample,length,kind,value` rows, where
+/// the value is the frequency of a tone or the index of a line. Both begin with a
+/// header row of the column names.
+///
+/// The first write error is kept and returned by [`CsvDiagnostics::flush`].
+pub struct CsvDiagnostics<W: Write> {
+    frequency_writer: W,
+    event_writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> CsvDiagnostics<W> {
+    pub fn new(frequency_writer: W, event_writer: W) -> Self {
+        let mut diagnostics = Self {
+            frequency_writer,
+            event_writer,
+            error: None,
+        };
+        let result = writeln!(diagnostics.frequency_writer, "sample,frequency")
+            .and_then(|()| writeln!(diagnostics.event_writer, "sample,length,kind,value"));
+        diagnostics.keep_error(result);
+        diagnostics
+    }
+
+    /// Return the frequency writer and the event writer, without flushing them.
+    pub fn into_inner(self) -> (W, W) {
+        (self.frequency_writer, self.event_writer)
+    }
+
+    /// Flush both writers, return the first error since the last flush.
+    pub fn flush(&mut self) -> io::Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.frequency_writer.flush()?;
+        self.event_writer.flush()
+    }
+
+    fn keep_error(&mut self, result: io::Result<()>) {
+        if let Err(e) = result
+            && self.error.is_none()
+        {
+            self.error = Some(e);
+        }
+    }
+}
+
+impl<W: Write> Diagnostics for CsvDiagnostics<W> {
+    fn frequency(&mut self, position: usize, frequencies: &[f32]) {
+        let result = frequencies
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, f)| writeln!(self.frequency_writer, "{},{:.2}", position + i, f));
+        self.keep_error(result);
+    }
+
+    fn tone(&mut self, tone: &DetectedTone) {
+        let result = writeln!(
+            self.event_writer,
+            "{},{},{:?},{:.2}",
+            tone.position, tone.length, tone.tone, tone.frequency
+        );
+        self.keep_error(result);
+    }
+
+    fn line(&mut self, line: &LineTiming) {
+        let result = writeln!(
+            self.event_writer,
+            "{},{},Line,{}",
+            line.position, line.length, line.index
+        );
+        self.keep_error(result);
+    }
+}
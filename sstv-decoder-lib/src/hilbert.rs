@@ -1,37 +1,76 @@
-use rustfft::FftPlanner;
+use std::sync::Arc;
+
 use rustfft::num_complex::Complex32;
+use rustfft::{Fft, FftPlanner};
 
 /// 对单一窗进行Hilbert解析信号计算
-pub fn hilbert_transform(x: &[f32]) -> Vec<Complex32> {
+fn hilbert_transform(fft: &dyn Fft<f32>, ifft: &dyn Fft<f32>, x: &[f32]) -> Vec<Complex32> {
     let n = x.len();
-    let mut planner = FftPlanner::<f32>::new();
     let mut input: Vec<Complex32> = x.iter().map(|&x| Complex32::new(x, 0.0)).collect();
-    let fft = planner.plan_fft_forward(n);
     fft.process(&mut input);
 
     // 构建滤波器
     let mut h = vec![0.0; n];
-    if n % 2 == 0 {
+    if n.is_multiple_of(2) {
         h[0] = 1.0;
         h[n / 2] = 1.0;
-        for i in 1..(n / 2) {
-            h[i] = 2.0;
-        }
+        h[1..(n / 2)].fill(2.0);
     } else {
         h[0] = 1.0;
-        for i in 1..((n + 1) / 2) {
-            h[i] = 2.0;
-        }
+        h[1..n.div_ceil(2)].fill(2.0);
     }
     for (xi, hi) in input.iter_mut().zip(h.iter()) {
         *xi *= *hi;
     }
 
     // 逆变换
-    let ifft = planner.plan_fft_inverse(n);
     ifft.process(&mut input);
     for x in &mut input {
         *x /= n as f32;
     }
     input
 }
+
+/// Window size of the sliding Hilbert transform.
+const WINDOW: usize = 2048;
+
+/// Samples at both edges of the window, which are distorted by the transform and dropped.
+const MARGIN: usize = 256;
+
+/// The longest time a sample waits inside the transform, before it is returned.
+pub const DELAY: usize = WINDOW - MARGIN;
+
+/// 滑动窗Hilbert变换
+///
+/// The windows overlap by two margins, and only the middle of each window is returned,
+/// so the result does not depend on how the stream is split into chunks. The result is
+/// delayed by up to one window.
+pub struct SlidingHilbert {
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    /// Samples not transformed yet, with the margin before them.
+    buffer: Vec<f32>,
+}
+
+impl SlidingHilbert {
+    pub fn new() -> Self {
+        let mut planner = FftPlanner::<f32>::new();
+        Self {
+            fft: planner.plan_fft_forward(WINDOW),
+            ifft: planner.plan_fft_inverse(WINDOW),
+            buffer: vec![0.0; MARGIN],
+        }
+    }
+
+    /// Add samples, return the analytic signal of every window completed.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<Complex32> {
+        self.buffer.extend_from_slice(samples);
+        let mut result = vec![];
+        while self.buffer.len() >= WINDOW {
+            let z = hilbert_transform(&*self.fft, &*self.ifft, &self.buffer[..WINDOW]);
+            result.extend_from_slice(&z[MARGIN..WINDOW - MARGIN]);
+            self.buffer.drain(..WINDOW - 2 * MARGIN);
+        }
+        result
+    }
+}
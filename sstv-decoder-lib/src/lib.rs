@@ -1,5 +1,10 @@
 mod bandpass_filter;
+pub mod diagnostics;
 mod hilbert;
-mod mode;
 pub mod sstv_decoder;
-mod sstv_image;
+
+pub use sstv_core::{
+    Channel, ColorModel, DecodeError, Family, I24, MODE_SPECS, Mode, ModeSpec, ParseModeError,
+    Sample, SstvError,
+    sstv_image::{ImageCreationError, SSTVImage},
+};
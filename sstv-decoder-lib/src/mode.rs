@@ -1,35 +0,0 @@
-/// Support modes.
-#[derive(PartialEq, Debug)]
-pub enum Mode {
-    None,
-    VisFind,
-    Scottie1,
-    Scottie2,
-    ScottieDx,
-    Martin1,
-    Martin2,
-    Robot36,
-    Robot72,
-    //  WrasseSc2_180,
-    //  P3,
-    //  P5,
-    //  P7,
-    //  Pd50,
-    //  Pd90,
-     Pd120,
-    //  Pd160,
-    //  Pd180,
-    //  Pd240,
-    //  Pd290,
-}
-
-//pub enum Sync {
-//    Sync,
-//    Unsync,
-//}
-
-// enum ColorMode {
-//     RGB,
-//     GBR,
-//     YUV,
-// }
@@ -1,396 +1,1088 @@
 // Copyright 2025 BenderBlog Rodriguez and Contributors.
 // SPDX-License-Identifier: 0BSD
 
-use std::{
-    collections::VecDeque,
-    f32::consts::PI,
-    fs::File,
-    io::{BufWriter, Write},
-    vec,
+use std::{collections::VecDeque, f32::consts::PI, time::Duration, vec};
+
+use sstv_core::{
+    Channel, ColorModel, DecodeError, Family, Mode, ModeSpec, Sample, sstv_image::SSTVImage,
+};
+
+use crate::{
+    bandpass_filter::BandpassFilter,
+    diagnostics::{DetectedTone, Diagnostics, LineTiming, Tone},
+    hilbert::{self, SlidingHilbert},
 };
 
-use image::{Rgb, RgbImage};
+/// What the decoder is doing, returned by [`SSTVDecoder::state`].
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DecoderState {
+    /// Waiting for a VIS header.
+    Idle,
+    /// A leader tone is heard, which could be the beginning of a VIS header.
+    Leader,
+    /// The header is found, the VIS code follows.
+    Vis,
+    /// Receiving a picture in the mode, `line` lines are decoded.
+    Receiving { mode: Mode, line: usize },
+    /// The picture in the mode is complete, waiting for the next VIS header.
+    Complete { mode: Mode },
+}
+
+/// How far the picture under decoding is, returned by [`SSTVDecoder::progress`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub mode: Mode,
+    /// Lines decoded.
+    pub lines_done: usize,
+    /// Lines of the picture.
+    pub lines_total: usize,
+    /// Time of the signal received since the beginning of the VIS header.
+    pub elapsed: Duration,
+    /// Time to send the picture, including the VIS header.
+    pub expected: Duration,
+}
 
-use crate::{bandpass_filter::bandpass_filter, hilbert::hilbert_transform, mode::Mode};
+/// Something which happened while decoding the stream, returned by [`SSTVDecoder::decode`].
+#[derive(Debug, Clone)]
+pub enum DecoderEvent {
+    /// The leader tones and the break of a VIS header are found.
+    HeaderDetected,
+    /// The VIS code after the header is decoded, the picture begins.
+    VisDecoded { mode: Mode, code: u8 },
+    /// A line of the picture is decoded, in [R, G, B] pixels.
+    ///
+    /// Robot 36 shares the chroma between a pair of lines, so the first line of a
+    /// pair is sent again once the second one arrives.
+    LineDecoded { index: usize, pixels: Vec<[u8; 3]> },
+    /// The picture is complete.
+    ImageComplete { mode: Mode, image: SSTVImage },
+    /// The picture is dropped, because the VIS code after the header is invalid or
+    /// [`SSTVDecoder::abort`] is called. The decoder waits for the next header.
+    Aborted(DecodeError),
+}
 
-fn get_sample_length_in_ms(sample_rate: f32) -> f32 {
-    sample_rate / 1000.0
+fn get_sample_length_in_ms(sample_rate: f32) -> f64 {
+    sample_rate as f64 / 1000.0
+}
+
+fn average(frequency_data: &[f32]) -> f32 {
+    frequency_data.iter().sum::<f32>() / frequency_data.len() as f32
 }
 
 pub struct SSTVDecoder {
-    pub mode: Mode,
-    pub sample_rate: f32,
+    state: DecoderState,
+    sample_rate: f32,
     /// Need store 1000ms data.
-    pub sample_queue: Box<VecDeque<f32>>,
-    /// Begin of the VIS Sample:
-    ///    Leader tone 1900Hz 300ms
-    ///    Break 1200Hz 10ms
-    ///    Leader tone 1900Hz 300ms
-    header_sample_num: usize,
+    sample_queue: VecDeque<f32>,
+    /// Index of the first sample of the queue since the decoder started.
+    queue_position: usize,
+    /// Running sums of the queue for the header search, kept between the calls so only
+    /// the new samples are added. The difference of two entries is the sum of the samples
+    /// between them, there is one entry more than the samples already added.
+    header_sums: VecDeque<f64>,
+    /// Index of the first sample of the latest VIS header, estimated from its start bit.
+    header_position: usize,
     /// For vis singles, total transmission time is 30 * 10 = 300ms
     vis_sample_num: usize,
     /// Optional video stuff.
     picture: Vec<Vec<[u8; 3]>>,
     counter: usize,
-    /// Debug file to store sample data
-    writer: BufWriter<File>,
+    /// Fraction of a sample left over when a line does not end on a sample boundary.
+    sample_remainder: f64,
+    /// Robot 36 only: the latest R-Y and B-Y scans, shared by a pair of lines.
+    robot_chroma: [Vec<u8>; 2],
+    /// Robot 36 only: the Y scan of the previous line, rebuilt when the other chroma arrives.
+    robot_previous_y: Vec<u8>,
+    /// Whether to correct the frequencies with the measured sync and black levels.
+    calibration: bool,
+    /// Measured frequency of the 1200hz sync pulses.
+    sync_level: f32,
+    /// Measured frequency of the 1500hz pulses, which is the black level.
+    black_level: f32,
+    /// Optional receiver of the internal data.
+    diagnostics: Option<Box<dyn Diagnostics + Send>>,
+    bandpass_filter: BandpassFilter,
+    hilbert: SlidingHilbert,
     /// Previous phase.
     prev: f32,
+    /// Events waiting to be returned by the current decode call.
+    events: Vec<DecoderEvent>,
 }
 
 impl SSTVDecoder {
-    pub fn new(sample_rate: f32) -> Self {
-        let file = std::fs::File::create("inst_freq_sliding_hilbert.csv").unwrap();
-        SSTVDecoder {
-            mode: Mode::None,
-            sample_rate: sample_rate,
-            sample_queue: Box::new(VecDeque::new()),
-            header_sample_num: (610.0 * get_sample_length_in_ms(sample_rate)) as usize,
+    /// Create a decoder of the stream at the sample rate.
+    ///
+    /// Will return an error if the sample rate is too low for the 3000hz bandpass filter.
+    pub fn new(sample_rate: f32) -> Result<Self, DecodeError> {
+        Ok(SSTVDecoder {
+            state: DecoderState::Idle,
+            sample_rate,
+            sample_queue: VecDeque::new(),
+            queue_position: 0,
+            header_sums: VecDeque::new(),
+            header_position: 0,
             vis_sample_num: (300.0 * get_sample_length_in_ms(sample_rate)) as usize,
             picture: vec![vec![]],
             counter: 0,
-            writer: std::io::BufWriter::new(file),
+            sample_remainder: 0.0,
+            robot_chroma: [vec![], vec![]],
+            robot_previous_y: vec![],
+            calibration: false,
+            sync_level: SYNC_FREQUENCY,
+            black_level: BLACK_FREQUENCY,
+            diagnostics: None,
+            bandpass_filter: BandpassFilter::new(sample_rate)?,
+            hilbert: SlidingHilbert::new(),
             prev: 0.0,
-        }
+            events: vec![],
+        })
     }
 
-    pub fn switch_sample(&mut self, sample_rate: f32) {
+    /// Switch to another sample rate, and forget the stream.
+    ///
+    /// Will return an error if the sample rate is too low, and keep the current one.
+    pub fn switch_sample(&mut self, sample_rate: f32) -> Result<(), DecodeError> {
+        self.bandpass_filter = BandpassFilter::new(sample_rate)?;
         self.sample_rate = sample_rate;
-        self.mode = Mode::None;
-        self.sample_queue.clear();
-        self.header_sample_num = (610.0 * get_sample_length_in_ms(sample_rate)) as usize;
         self.vis_sample_num = (300.0 * get_sample_length_in_ms(sample_rate)) as usize;
+        self.reset();
+        Ok(())
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    pub fn state(&self) -> DecoderState {
+        self.state
+    }
+
+    /// How far the picture under decoding is, None if there is no picture under decoding.
+    pub fn progress(&self) -> Option<Progress> {
+        let DecoderState::Receiving { mode, line } = self.state else {
+            return None;
+        };
+        let received = self.queue_position + self.sample_queue.len() - self.header_position;
+        Some(Progress {
+            mode,
+            lines_done: line,
+            lines_total: mode.spec().height,
+            elapsed: Duration::from_secs_f64(received as f64 / self.sample_rate as f64),
+            expected: mode.duration(),
+        })
+    }
+
+    /// Forget the stream, as if the decoder were just created.
+    ///
+    /// The calibration and the diagnostics are kept.
+    pub fn reset(&mut self) {
+        self.state = DecoderState::Idle;
+        self.sample_queue.clear();
+        self.queue_position = 0;
+        self.header_sums.clear();
+        self.header_position = 0;
         self.picture = vec![vec![]];
         self.counter = 0;
+        self.sample_remainder = 0.0;
+        self.sync_level = SYNC_FREQUENCY;
+        self.black_level = BLACK_FREQUENCY;
+        self.bandpass_filter.reset();
+        self.hilbert = SlidingHilbert::new();
         self.prev = 0.0;
+        self.events.clear();
     }
 
-    /// Decoder
+    /// Drop the picture under decoding, and wait for the next VIS header.
+    ///
+    /// The stream already given to the decoder is kept, so a header inside it is
+    /// still found by the next [`SSTVDecoder::decode`] call. If a picture was under
+    /// decoding, that call begins with [`DecoderEvent::Aborted`].
+    pub fn abort(&mut self) {
+        if matches!(
+            self.state,
+            DecoderState::Vis | DecoderState::Receiving { .. }
+        ) {
+            self.events
+                .push(DecoderEvent::Aborted(DecodeError::Aborted));
+        }
+        self.state = DecoderState::Idle;
+        self.picture = vec![vec![]];
+        self.counter = 0;
+        self.sample_remainder = 0.0;
+        self.robot_previous_y = vec![];
+    }
+
+    /// Correct the frequencies with the sync and black levels measured from the signal,
+    /// so a mistuned receiver still gives the correct brightness. Disabled by default.
+    pub fn set_calibration(&mut self, calibration: bool) {
+        self.calibration = calibration;
+    }
+
+    /// Send the frequency track, the tones and the line timings to the diagnostics.
+    pub fn set_diagnostics(&mut self, diagnostics: impl Diagnostics + Send + 'static) {
+        self.diagnostics = Some(Box::new(diagnostics));
+    }
+
+    /// Stop sending data to the diagnostics, and return it.
+    pub fn take_diagnostics(&mut self) -> Option<Box<dyn Diagnostics + Send>> {
+        self.diagnostics.take()
+    }
+
+    /// Report a tone to the diagnostics, its position is relative to the beginning of the queue.
+    fn report_tone(&mut self, tone: Tone, start: usize, frequency_data: &[f32]) {
+        if let Some(diagnostics) = self.diagnostics.as_mut() {
+            diagnostics.tone(&DetectedTone {
+                tone,
+                position: self.queue_position + start,
+                length: frequency_data.len(),
+                frequency: average(frequency_data),
+            });
+        }
+    }
+
+    /// Drop the given number of samples from the queue.
+    fn pop_samples(&mut self, count: usize) {
+        let count = count.min(self.sample_queue.len());
+        self.sample_queue.drain(0..count);
+        self.queue_position += count;
+        if count < self.header_sums.len() {
+            self.header_sums.drain(0..count);
+        } else {
+            self.header_sums.clear();
+        }
+    }
+
+    /// Convert a duration into a sample count at the current sample rate.
+    fn ms_to_samples(&self, duration_in_ms: f64) -> usize {
+        (duration_in_ms * get_sample_length_in_ms(self.sample_rate)) as usize
+    }
+
+    /// Drop the samples of the given duration from the queue.
+    ///
+    /// The fractional part is carried over to the next call, so a long picture does
+    /// not drift away from the line timing.
+    fn pop_samples_in_ms(&mut self, duration_in_ms: f64) {
+        let exact =
+            duration_in_ms * get_sample_length_in_ms(self.sample_rate) + self.sample_remainder;
+        let count = exact.floor();
+        self.sample_remainder = exact - count;
+        self.pop_samples(count as usize);
+    }
+
+    /// Convert the pcm data into the instantaneous frequency of every sample.
     fn decoder(&mut self, samples: &[f32]) -> Vec<f32> {
-        let samples = bandpass_filter(samples, self.sample_rate);
-        let z = hilbert_transform(&samples);
-        let mut phase: Vec<f32> = z.iter().map(|c| c.arg()).collect();
-        for i in 0..phase.len() {
-            let mut dp = phase[i] - self.prev;
-            while dp > PI {
-                dp -= 2.0 * PI;
-            }
-            while dp < -PI {
-                dp += 2.0 * PI;
-            }
-            phase[i] = self.prev + dp;
-            self.prev = phase[i];
-        }
-        let to_return = phase
-            .windows(2)
-            .map(|w| {
-                let to_return = (self.sample_rate * (w[1] - w[0]) / (2.0 * PI)).abs();
-                if to_return > 3000.0 {
-                    3000.0
-                } else if to_return < 1000.0 {
-                    1000.0
-                } else {
-                    to_return
+        let samples = self.bandpass_filter.run(samples);
+        let z = self.hilbert.process(&samples);
+        let to_return: Vec<f32> = z
+            .iter()
+            .map(|c| {
+                let phase = c.arg();
+                let mut dp = phase - self.prev;
+                if dp > PI {
+                    dp -= 2.0 * PI;
+                } else if dp < -PI {
+                    dp += 2.0 * PI;
                 }
+                self.prev = phase;
+                (self.sample_rate * dp / (2.0 * PI))
+                    .abs()
+                    .clamp(1000.0, 3000.0)
             })
             .collect();
-        // let f = std::fs::File::create_new("inst_freq_hilbert.csv");
-        // let mut w = std::io::BufWriter::new(f);
-        for &f in &to_return {
-            writeln!(self.writer, "{:.2}", f).unwrap();
+        if let Some(diagnostics) = self.diagnostics.as_mut() {
+            diagnostics.frequency(self.queue_position + self.sample_queue.len(), &to_return);
         }
         to_return
     }
 
-    /// Check the header
-    fn get_header(&mut self, frequency_data: &[f32]) -> bool {
-        let leader_duration_sample = (300.0 * get_sample_length_in_ms(self.sample_rate)) as usize;
-        let break_duration_sample = (10.0 * get_sample_length_in_ms(self.sample_rate)) as usize;
+    ///
+    /// Look for the VIS header inside the queue.
+    ///
+    /// The header is made of:
+    ///    Leader tone 1900Hz 300ms
+    ///    Break 1200Hz 10ms
+    ///    Leader tone 1900Hz 300ms
+    ///    VIS start bit 1200Hz 30ms
+    ///
+    /// A mistuned receiver moves every tone by the same offset, so the leader tone is
+    /// accepted within [`MISTUNING_TOLERANCE`], and the other tones are checked against it.
+    ///
+    /// Only the last 10ms of the first leader tone is checked, and only when all of it is
+    /// inside the queue, so the recording could begin in the middle of it. A shorter part
+    /// could still hold the start-up of the filters.
+    ///
+    /// Return the index of the VIS start bit. The samples which could not be a part of
+    /// a header are dropped from the queue.
+    ///
+    fn find_header(&mut self) -> Option<usize> {
+        let leader = self.ms_to_samples(300.0);
+        let break_length = self.ms_to_samples(10.0);
+        let start_bit = self.ms_to_samples(30.0);
+        let first_leader = self.ms_to_samples(10.0);
+        // The break, the second leader tone, then the start bit, which is looked for
+        // within one break length around its expected position.
+        let needed = break_length + leader + break_length + start_bit;
+        if self.sample_queue.len() < needed {
+            return None;
+        }
 
-        if frequency_data.len() != self.header_sample_num {
-            eprintln!("Error: PCM data length is not equal to a VIS header's length.");
-            return false;
+        if self.header_sums.is_empty() {
+            self.header_sums.push_back(0.0);
+        }
+        let added = self.header_sums.len() - 1;
+        let mut sum = self.header_sums[added];
+        for &f in self.sample_queue.range(added..) {
+            sum += f as f64;
+            self.header_sums.push_back(sum);
         }
+        let sums = &self.header_sums;
+        let mean =
+            |start: usize, end: usize| ((sums[end] - sums[start]) / (end - start) as f64) as f32;
+        let near =
+            |start: usize, end: usize, frequency: f32| (mean(start, end) - frequency).abs() <= 50.0;
 
-        let first_leader_tone = &frequency_data[0..leader_duration_sample]
-            .iter()
-            .sum::<f32>()
-            / leader_duration_sample as f32;
-        let break_tone_freq = &frequency_data
-            [leader_duration_sample..leader_duration_sample + break_duration_sample]
-            .iter()
-            .sum::<f32>()
-            / break_duration_sample as f32;
-        let second_leader_tone = &frequency_data[leader_duration_sample + break_duration_sample
-            ..2 * leader_duration_sample + break_duration_sample]
-            .iter()
-            .sum::<f32>()
-            / leader_duration_sample as f32;
+        let last_start = self.sample_queue.len() - needed;
+        for i in 0..=last_start {
+            let leader_start = i + break_length;
+            let leader_end = leader_start + leader;
+            let leader_frequency = mean(leader_start, leader_end - break_length);
+            let offset = leader_frequency - 1900.0;
+            if offset.abs() > MISTUNING_TOLERANCE || !near(i, leader_start, 1200.0 + offset) {
+                continue;
+            }
+            let first_leader_start = i.checked_sub(first_leader);
+            if let Some(start) = first_leader_start
+                && !near(start, i, leader_frequency)
+            {
+                continue;
+            }
 
-        println!(
-            "first_leader_tone: {}, break_tone_freq: {}, second_leader_tone: {}",
-            first_leader_tone, break_tone_freq, second_leader_tone
-        );
+            // The start bit begins where the frequency steps down the most.
+            let step = |t: usize| mean(t - break_length, t) - mean(t, t + break_length);
+            let edge = (leader_end - break_length..=leader_end + break_length)
+                .max_by(|&a, &b| step(a).total_cmp(&step(b)))?;
+            if !near(edge, edge + start_bit, 1200.0 + offset) {
+                continue;
+            }
 
-        (first_leader_tone - 1900.0).abs() <= 50.0
-            && (break_tone_freq - 1200.0) <= 50.0
-            && (second_leader_tone - 1900.0) <= 50.0
+            let frequency_data: Vec<f32> = self.sample_queue.range(..edge).cloned().collect();
+            if let Some(start) = first_leader_start {
+                self.report_tone(Tone::Leader, start, &frequency_data[start..i]);
+            }
+            self.report_tone(Tone::Break, i, &frequency_data[i..leader_start]);
+            self.report_tone(
+                Tone::Leader,
+                leader_start,
+                &frequency_data[leader_start..edge],
+            );
+            return Some(edge);
+        }
+
+        // Keep the samples which could still be the beginning of a header.
+        self.pop_samples((last_start + 1).saturating_sub(first_leader));
+        None
     }
 
-    /// Check the vis
-    fn decode_vis(&mut self, frequency_data: &[f32]) -> Mode {
-        if frequency_data.len() != self.vis_sample_num {
-            eprintln!("Error: PCM data length is not equal to a VIS signal's length.");
-            return Mode::None;
+    /// Whether the end of the queue is a leader tone, which could begin a header.
+    fn hears_leader(&self) -> bool {
+        let length = self.ms_to_samples(10.0);
+        if length == 0 || self.sample_queue.len() < length {
+            return false;
         }
+        let tail: Vec<f32> = self
+            .sample_queue
+            .range(self.sample_queue.len() - length..)
+            .cloned()
+            .collect();
+        (average(&tail) - 1900.0).abs() <= MISTUNING_TOLERANCE
+    }
+
+    /// Check the vis, return the mode with the VIS code.
+    ///
+    /// Will return an error if the parity is wrong or the code is unknown.
+    fn decode_vis(
+        &mut self,
+        frequency_data: &[f32],
+    ) -> Result<(&'static ModeSpec, u8), DecodeError> {
+        debug_assert_eq!(frequency_data.len(), self.vis_sample_num);
 
         // Check the bitstream.
         // 1 as true, 0 as false
         let bit_size = self.vis_sample_num / 10;
+        for bit_index in 0..10 {
+            let start = bit_index * bit_size;
+            self.report_tone(
+                Tone::VisBit(bit_index),
+                start,
+                &frequency_data[start..start + bit_size],
+            );
+        }
+
+        // The start bit and the stop bit are 1200hz, as the sync pulses.
+        let middle_half = |bit_index: usize| {
+            &frequency_data
+                [bit_index * bit_size + bit_size / 4..(bit_index + 1) * bit_size - bit_size / 4]
+        };
+        self.sync_level = (average(middle_half(0)) + average(middle_half(9))) / 2.0;
+
+        // 1100hz is below the 1200hz of the start and stop bits, 1300hz is above, even
+        // when the receiver is mistuned.
+        let bit = |bit_index: usize| {
+            average(&frequency_data[bit_index * bit_size..(bit_index + 1) * bit_size])
+                < self.sync_level
+        };
 
         let mut vis_code: u8 = 0;
         let mut true_count: u8 = 0;
 
         for bit_index in 1..8 {
-            let section = if (&frequency_data[bit_index * bit_size..(bit_index + 1) * bit_size]
-                .iter()
-                .sum::<f32>()
-                / bit_size as f32
-                - 1100.0)
-                <= 50.0
-            {
-                true
-            } else {
-                false
-            };
-            println!(
-                "{} bit is {} from {}.",
-                bit_index,
-                section,
-                (&frequency_data[bit_index * bit_size..(bit_index + 1) * bit_size])
-                    .iter()
-                    .sum::<f32>()
-                    / bit_size as f32
-            );
+            let section = bit(bit_index);
 
             if section {
                 true_count += 1;
             }
 
-            vis_code += if section { 1 } else { 0 } << (bit_index - 1);
+            vis_code += u8::from(section) << (bit_index - 1);
         }
-        let parity = ((&frequency_data[9 * bit_size..10 * bit_size])
-            .iter()
-            .sum::<f32>()
-            / bit_size as f32
-            - 1100.0)
-            .abs()
-            <= 50.0;
+        // Bit 0 is the start bit, bit 8 is the parity bit and bit 9 is the stop bit.
+        let parity = bit(8);
 
         if (true_count % 2 == 1) != parity {
-            eprintln!(
-                "Error decoding VIS header (invalid parity bit). {:} {:}",
-                vis_code, parity
-            );
-            return Mode::None;
-        }
-
-        let mode = match vis_code {
-            //   60 => Mode::Scottie1,
-            //   56 => Mode::Scottie2,
-            //   76 => Mode::ScottieDx,
-            //  44 => Mode::Martin1,
-            //   40 => Mode::Martin2,
-            //   8 => Mode::Robot36,
-            //  12 => Mode::Robot72,
-            //   55 => Mode::WrasseSc2_180,
-            //   113 => Mode::P3,
-            //   114 => Mode::P5,
-            //   115 => Mode::P7,
-            //   93 => Mode::Pd50,
-            //   99 => Mode::Pd90,
-            95 => Mode::Pd120,
-            //   98 => Mode::Pd160,
-            //   96 => Mode::Pd180,
-            //   97 => Mode::Pd240,
-            //   94 => Mode::Pd290,
-            _ => Mode::None,
-        };
-        println!("VIS Mode is {:?}, code {}.", mode, vis_code);
+            return Err(DecodeError::VisParity(vis_code));
+        }
 
-        mode
+        let spec =
+            ModeSpec::from_vis_code(vis_code).ok_or(DecodeError::UnknownVisCode(vis_code))?;
+        Ok((spec, vis_code))
     }
 
     /// Get line info
-    fn decode_line_info(&mut self, frequency_data: &[f32], pixels: usize) -> Vec<u8> {
-        let mut decoded_pixel = vec![0; pixels];
-        let freq = &frequency_data;
-        let freq_per_pixel = freq.len() / pixels;
-        for i in 0..pixels - 1 {
-            let freq = freq[i * freq_per_pixel..(i + 1) * freq_per_pixel]
-                .iter()
-                .sum::<f32>()
-                / freq_per_pixel as f32;
-            decoded_pixel[i] = ((freq - 1500.0) / 800.0) as u8;
-        }
-        decoded_pixel
-    }
-
-    /// Decode the stream of data, by adding 1ms data.
-    pub fn decode(&mut self, pcm_data: &[f32]) {
-        if pcm_data.len() == 0 {
-            eprintln!("PCM data is empty!");
+    ///
+    /// Every pixel is the average frequency of its part of the scan.
+    fn decode_line_info(&self, frequency_data: &[f32], pixels: usize) -> Vec<u8> {
+        if frequency_data.is_empty() {
+            return vec![0; pixels];
+        }
+        let freq_per_pixel = frequency_data.len() as f32 / pixels as f32;
+        (0..pixels)
+            .map(|i| {
+                let start = ((i as f32 * freq_per_pixel) as usize).min(frequency_data.len() - 1);
+                let end = (((i + 1) as f32 * freq_per_pixel) as usize)
+                    .clamp(start + 1, frequency_data.len());
+                self.frequency_to_intensity(average(&frequency_data[start..end]))
+            })
+            .collect()
+    }
+
+    /// Convert a frequency into the 0-255 intensity, 1500hz as black and 2300hz as white.
+    ///
+    /// With calibration enabled, the measured sync and black levels are moved back to
+    /// 1200hz and 1500hz first.
+    fn frequency_to_intensity(&self, frequency: f32) -> u8 {
+        let frequency = if self.calibration {
+            SYNC_FREQUENCY
+                + (frequency - self.sync_level) * (BLACK_FREQUENCY - SYNC_FREQUENCY)
+                    / (self.black_level - self.sync_level)
+        } else {
+            frequency
+        };
+        ((frequency - BLACK_FREQUENCY) / (WHITE_FREQUENCY - BLACK_FREQUENCY) * 255.0)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    }
+
+    /// Measure a sync pulse and a black pulse of the line at the beginning of the queue,
+    /// both in (start, time) relative to the beginning of the line.
+    ///
+    /// The edges of the pulses are smeared by the filters, so they are skipped. When the
+    /// black pulse is too short for that, only the sync pulse is measured and the black
+    /// level moves along with it. The levels are averaged over the lines.
+    fn measure_levels(&mut self, sync: (f64, f64), black: (f64, f64)) {
+        let steady = |(start, time): (f64, f64)| {
+            (time >= LEVEL_EDGE_TIME * 2.0 + LEVEL_STEADY_TIME).then(|| {
+                self.ms_to_samples(start + LEVEL_EDGE_TIME)
+                    ..self.ms_to_samples(start + time - LEVEL_EDGE_TIME)
+            })
+        };
+        let Some(sync) = steady(sync) else {
+            return;
+        };
+        let black = steady(black);
+        let end = black
+            .as_ref()
+            .map_or(sync.end, |black| black.end.max(sync.end));
+        if end > self.sample_queue.len() {
+            return;
+        }
+
+        let sync_data: Vec<f32> = self.sample_queue.range(sync.clone()).cloned().collect();
+        self.report_tone(Tone::Sync, sync.start, &sync_data);
+        let sync_level = average(&sync_data);
+        let black_level = match black {
+            Some(black) => {
+                let black_data: Vec<f32> =
+                    self.sample_queue.range(black.clone()).cloned().collect();
+                self.report_tone(Tone::Black, black.start, &black_data);
+                average(&black_data)
+            }
+            None => self.black_level + sync_level - self.sync_level,
+        };
+
+        // Ignore a line without a sync pulse or a black pulse, which would invert the picture.
+        if (black_level - sync_level - (BLACK_FREQUENCY - SYNC_FREQUENCY)).abs() > 150.0 {
             return;
         }
+        self.sync_level += (sync_level - self.sync_level) * LEVEL_SMOOTHING;
+        self.black_level += (black_level - self.black_level) * LEVEL_SMOOTHING;
+    }
+
+    /// Decode the stream of data in any sample format, return what happened in the meantime.
+    ///
+    /// Will return an error if a sample is not a finite number, and ignore the data,
+    /// since it would break the filters for the rest of the stream.
+    pub fn decode<S: Sample>(&mut self, pcm_data: &[S]) -> Result<Vec<DecoderEvent>, DecodeError> {
+        let pcm_data: Vec<f32> = pcm_data.iter().map(|sample| sample.to_f32()).collect();
+        if let Some(index) = pcm_data.iter().position(|sample| !sample.is_finite()) {
+            return Err(DecodeError::InvalidSample(index));
+        }
+        if pcm_data.is_empty() {
+            return Ok(std::mem::take(&mut self.events));
+        }
+        Ok(self.run(&pcm_data))
+    }
+
+    /// Decode what is left inside the filters at the end of the stream, return what
+    /// happened in the meantime.
+    ///
+    /// The Hilbert transform only returns whole windows, so the end of the stream is
+    /// held back until more samples come, and the last lines of a picture would never
+    /// be decoded. This pads the stream with silence to push them out. The decoder
+    /// could still be given more data afterwards, as if the stream had a short gap.
+    pub fn finish(&mut self) -> Vec<DecoderEvent> {
+        let padding = hilbert::DELAY + self.ms_to_samples(FINISH_PADDING_TIME);
+        self.run(&vec![0.0; padding])
+    }
 
+    /// Run the checked pcm data through the filters and the state machine.
+    fn run(&mut self, pcm_data: &[f32]) -> Vec<DecoderEvent> {
         // Sample the frequency
         let decoded_data = self.decoder(pcm_data);
         self.sample_queue.extend(decoded_data);
 
-        // If no mod and sample_time is 690
-        if self.mode == Mode::None && self.sample_queue.len() >= self.header_sample_num {
-            if self.get_header(
-                &self
-                    .sample_queue
-                    .range(0..(self.header_sample_num))
-                    .cloned()
-                    .collect::<Vec<f32>>(),
-            ) {
-                self.mode = Mode::VisFind;
-                for _i in 0..self.header_sample_num {
-                    self.sample_queue.pop_front();
+        loop {
+            match self.state {
+                DecoderState::Idle | DecoderState::Leader | DecoderState::Complete { .. } => {
+                    let Some(start) = self.find_header() else {
+                        if self.hears_leader() {
+                            self.state = DecoderState::Leader;
+                        } else if self.state == DecoderState::Leader {
+                            self.state = DecoderState::Idle;
+                        }
+                        break;
+                    };
+                    self.pop_samples(start);
+                    self.header_position = self
+                        .queue_position
+                        .saturating_sub(self.ms_to_samples(300.0 + 10.0 + 300.0));
+                    self.state = DecoderState::Vis;
+                    self.events.push(DecoderEvent::HeaderDetected);
+                }
+                DecoderState::Vis => {
+                    if self.sample_queue.len() < self.vis_sample_num {
+                        break;
+                    }
+                    self.decode_vis_in_queue();
+                }
+                DecoderState::Receiving { mode, .. } => {
+                    let spec = mode.spec();
+                    let line_time = self.line_time_in_ms(spec);
+                    if self.sample_queue.len() < self.ms_to_samples(line_time) {
+                        break;
+                    }
+                    self.decode_line(spec, line_time);
                 }
             }
-            println!("After VIS check, current mode: {:?}", self.mode);
-            println!(
-                "Length of buffer {}, minus {}",
-                self.sample_queue.len(),
-                self.header_sample_num,
-            );
-            return;
         }
 
-        if self.mode == Mode::VisFind && self.sample_queue.len() >= self.vis_sample_num {
-            self.mode = self.decode_vis(
-                &self
-                    .sample_queue
-                    .range(0..(self.vis_sample_num))
-                    .cloned()
-                    .collect::<Vec<f32>>(),
-            );
-            for _i in 0..self.vis_sample_num {
-                self.sample_queue.pop_front();
-            }
-            if self.mode != Mode::None {
-                self.picture = vec![vec![[0; 3]; 640]; 496].into();
+        std::mem::take(&mut self.events)
+    }
+
+    /// Decode the VIS code at the beginning of the queue, and get ready for the picture.
+    fn decode_vis_in_queue(&mut self) {
+        let result = self.decode_vis(
+            &self
+                .sample_queue
+                .range(0..(self.vis_sample_num))
+                .cloned()
+                .collect::<Vec<f32>>(),
+        );
+        self.pop_samples(self.vis_sample_num);
+        match result {
+            Ok((spec, code)) => self.start_picture(spec, code),
+            Err(e) => {
+                self.state = DecoderState::Idle;
+                self.events.push(DecoderEvent::Aborted(e));
             }
-            println!("After VIS check, current mode: {:?}", self.mode);
-            println!(
-                "Length of buffer {}, minus {}",
-                self.sample_queue.len(),
-                self.vis_sample_num,
-            );
-            return;
         }
+    }
 
-        if self.mode == Mode::Pd120
-            && self.sample_queue.len()
-                >= ((121.6 * 4.0 + 20.0 + 2.08) * get_sample_length_in_ms(self.sample_rate))
-                    as usize
-        {
-            self.decode_in_pd120();
-            for _i in 0..((121.6 * 4.0 + 20.0 + 2.08) * get_sample_length_in_ms(self.sample_rate))
-                as usize
-            {
-                self.sample_queue.pop_front();
+    /// Get ready for the picture in the mode.
+    fn start_picture(&mut self, spec: &ModeSpec, code: u8) {
+        let (width, height) = (spec.width, spec.height);
+        self.state = DecoderState::Receiving {
+            mode: spec.mode,
+            line: 0,
+        };
+        self.picture = vec![vec![[0; 3]; width]; height];
+        self.counter = 0;
+        self.sample_remainder = 0.0;
+        self.robot_chroma = [vec![128; width], vec![128; width]];
+        self.robot_previous_y = vec![];
+        self.black_level = self.sync_level + BLACK_FREQUENCY - SYNC_FREQUENCY;
+        self.events.push(DecoderEvent::VisDecoded {
+            mode: spec.mode,
+            code,
+        });
+    }
+
+    /// Decode the next part of the picture, which is already inside the queue.
+    fn decode_line(&mut self, spec: &ModeSpec, line_time: f64) {
+        if let Some(diagnostics) = self.diagnostics.as_mut() {
+            diagnostics.line(&LineTiming {
+                index: self.counter,
+                position: self.queue_position,
+                length: (line_time * get_sample_length_in_ms(self.sample_rate)) as usize,
+            });
+        }
+
+        match spec.family {
+            Family::Scottie => self.decode_in_scottie(spec, line_time),
+            Family::Martin => self.decode_in_martin(spec, line_time),
+            Family::Robot if spec.color_model == ColorModel::Monochrome => {
+                self.decode_in_robot_bw(spec, line_time)
             }
-            println!(
-                "Length of buffer {}, minus {}",
-                self.sample_queue.len(),
-                ((121.6 * 4.0 + 20.0 + 2.08) * get_sample_length_in_ms(self.sample_rate)),
-            );
-            return;
+            // R-Y and B-Y on alternate lines.
+            Family::Robot if spec.scan_order.len() == 2 => self.decode_in_robot36(spec, line_time),
+            Family::Robot => self.decode_in_robot72(spec, line_time),
+            Family::Wraase => self.decode_in_wraase_sc2(spec, line_time),
+            Family::Pasokon => self.decode_in_pasokon(spec, line_time),
+            Family::Pd => self.decode_in_pd(spec, line_time),
+        }
+
+        self.state = DecoderState::Receiving {
+            mode: spec.mode,
+            line: self.counter.min(self.picture.len()),
+        };
+        if self.counter >= self.picture.len() {
+            self.finish_picture(spec);
         }
+    }
 
-        println!("Current buffer length {}", self.sample_queue.len());
-        return;
+    /// The time of the next part of the picture to decode, including the sync pulse.
+    fn line_time_in_ms(&self, spec: &ModeSpec) -> f64 {
+        let line_time = spec.line_time();
+        // The Scottie "starting" sync pulse only appears before the first line.
+        if spec.family == Family::Scottie && self.counter == 0 {
+            spec.sync_time + line_time
+        } else {
+            line_time
+        }
     }
 
-    fn decode_in_pd120(&mut self) {
-        if self.counter >= 494 {
-            self.counter = 0;
-            self.mode = Mode::None;
+    /// Copy the frequency data of the current line out of the queue.
+    fn get_line_data(&self, line_time: f64) -> Vec<f32> {
+        let sample_count = self.ms_to_samples(line_time).min(self.sample_queue.len());
+        self.sample_queue.range(0..sample_count).cloned().collect()
+    }
 
-            let height = self.picture.len();
-            let width = if height > 0 { self.picture[0].len() } else { 0 };
-            let mut img = RgbImage::new(width as u32, height as u32);
-            for (y, row) in self.picture.iter().enumerate() {
-                for (x, &rgb) in row.iter().enumerate() {
-                    img.put_pixel(x as u32, y as u32, Rgb(rgb));
-                }
+    /// Send the decoded line out.
+    fn line_decoded(&mut self, index: usize) {
+        self.events.push(DecoderEvent::LineDecoded {
+            index,
+            pixels: self.picture[index].clone(),
+        });
+    }
+
+    /// Send the picture out and wait for the next one.
+    fn finish_picture(&mut self, spec: &ModeSpec) {
+        self.counter = 0;
+        self.state = DecoderState::Complete { mode: spec.mode };
+
+        // Every line is as wide as the mode, so the size always matches.
+        if let Ok(image) = SSTVImage::new(spec.width, spec.height, self.picture.concat()) {
+            self.events.push(DecoderEvent::ImageComplete {
+                mode: spec.mode,
+                image,
+            });
+        }
+    }
+
+    ///
+    /// Scottie mode
+    ///
+    /// Scan Sequence: Separator, Green, Separator, Blue, Sync, Porch, Red
+    ///
+    /// The sync pulse is placed in the middle of the line, just before the red scan,
+    /// so the line is decoded from the separator pulse before the green scan. A
+    /// "starting" sync pulse is sent only once before the first line.
+    ///
+    fn decode_in_scottie(&mut self, spec: &ModeSpec, line_time: f64) {
+        let mut line_time = line_time;
+        if self.counter == 0 {
+            self.pop_samples_in_ms(spec.sync_time);
+            line_time -= spec.sync_time;
+        }
+
+        let scan_time = spec.scan_time(spec.scan_order[0]);
+
+        // separator-green-separator-blue-sync-porch-red
+        let green_start = spec.separator_time;
+        let blue_start = green_start + scan_time + spec.separator_time;
+        let red_start = blue_start + scan_time + spec.sync_time + spec.porch_time;
+
+        let sync_start = blue_start + scan_time;
+        self.measure_levels(
+            (sync_start, spec.sync_time),
+            (sync_start + spec.sync_time, spec.porch_time),
+        );
+        self.decode_rgb_line(
+            spec,
+            line_time,
+            [green_start, blue_start, red_start],
+            scan_time,
+        );
+    }
+
+    ///
+    /// Martin mode
+    ///
+    /// Scan Sequence: Sync, Porch, Green, Separator, Blue, Separator, Red, Separator
+    ///
+    fn decode_in_martin(&mut self, spec: &ModeSpec, line_time: f64) {
+        let scan_time = spec.scan_time(spec.scan_order[0]);
+
+        // sync-porch-green-separator-blue-separator-red-separator
+        let green_start = spec.sync_time + spec.porch_time;
+        let blue_start = green_start + scan_time + spec.separator_time;
+        let red_start = blue_start + scan_time + spec.separator_time;
+
+        self.measure_levels((0.0, spec.sync_time), (spec.sync_time, spec.porch_time));
+        self.decode_rgb_line(
+            spec,
+            line_time,
+            [green_start, blue_start, red_start],
+            scan_time,
+        );
+    }
+
+    ///
+    /// WRASSE SC2 modes
+    ///
+    /// Scan Sequence: Sync, Porch, Red, Green, Blue
+    ///
+    fn decode_in_wraase_sc2(&mut self, spec: &ModeSpec, line_time: f64) {
+        let scan_time = spec.scan_time(spec.scan_order[0]);
+
+        // sync-porch-red-green-blue
+        let red_start = spec.sync_time + spec.porch_time;
+        let green_start = red_start + scan_time;
+        let blue_start = green_start + scan_time;
+
+        self.measure_levels((0.0, spec.sync_time), (spec.sync_time, spec.porch_time));
+        self.decode_rgb_line(
+            spec,
+            line_time,
+            [red_start, green_start, blue_start],
+            scan_time,
+        );
+    }
+
+    ///
+    /// PASOKON "P" modes
+    ///
+    /// Scan Sequence: Sync, Porch, Red, Porch, Green, Porch, Blue, Porch
+    ///
+    fn decode_in_pasokon(&mut self, spec: &ModeSpec, line_time: f64) {
+        let scan_time = spec.scan_time(spec.scan_order[0]);
+
+        // sync-porch-red-porch-green-porch-blue-porch
+        let red_start = spec.sync_time + spec.porch_time;
+        let green_start = red_start + scan_time + spec.separator_time;
+        let blue_start = green_start + scan_time + spec.separator_time;
+
+        self.measure_levels((0.0, spec.sync_time), (spec.sync_time, spec.porch_time));
+        self.decode_rgb_line(
+            spec,
+            line_time,
+            [red_start, green_start, blue_start],
+            scan_time,
+        );
+    }
+
+    /// Decode one line of the RGB modes into the picture, and move to the next line.
+    ///
+    /// The start time of the scans are given in the scan order of the mode, relative
+    /// to the beginning of the line.
+    fn decode_rgb_line(
+        &mut self,
+        spec: &ModeSpec,
+        line_time: f64,
+        scan_start: [f64; 3],
+        scan_time: f64,
+    ) {
+        let pixel_count = self.picture[self.counter].len();
+        let data_to_parse = self.get_line_data(line_time);
+        let division = |start: f64| {
+            self.ms_to_samples(start).min(data_to_parse.len())
+                ..self
+                    .ms_to_samples(start + scan_time)
+                    .min(data_to_parse.len())
+        };
+
+        let mut lines: [Vec<u8>; 3] = Default::default();
+        for (channel, start) in spec.scan_order.iter().zip(scan_start) {
+            lines[channel.index()] =
+                self.decode_line_info(&data_to_parse[division(start)], pixel_count);
+        }
+
+        let [line_r, line_g, line_b] = lines;
+        for i in 0..pixel_count {
+            self.picture[self.counter][i] = [line_r[i], line_g[i], line_b[i]];
+        }
+        self.line_decoded(self.counter);
+
+        self.pop_samples_in_ms(line_time);
+        self.counter += 1;
+    }
+
+    ///
+    /// Robot 36 mode
+    ///
+    /// Scan Sequence: Sync, Porch, Y, Separator, Porch, R-Y or B-Y
+    ///
+    /// The separator pulse tells which chroma the line carries: 1500hz for R-Y and
+    /// 2300hz for B-Y. Each chroma is shared by a pair of lines, so when a line arrives
+    /// the previous line is rebuilt with the new chroma as well.
+    ///
+    fn decode_in_robot36(&mut self, spec: &ModeSpec, line_time: f64) {
+        let pixel_count = self.picture[self.counter].len();
+
+        let data_to_parse = self.get_line_data(line_time);
+
+        // sync-porch-y-separator-porch-chroma
+        let y_start = spec.sync_time + spec.porch_time;
+        let separator_start = y_start + spec.scan_time(Channel::Y);
+        let chroma_start = separator_start + spec.separator_time + spec.separator_porch_time;
+        let division = |start: f64, end: f64| {
+            self.ms_to_samples(start).min(data_to_parse.len())
+                ..self.ms_to_samples(end).min(data_to_parse.len())
+        };
+
+        let line_y = self.decode_line_info(
+            &data_to_parse[division(y_start, separator_start)],
+            pixel_count,
+        );
+        let line_chroma = self.decode_line_info(
+            &data_to_parse[division(chroma_start, line_time)],
+            pixel_count,
+        );
+
+        // Only look at the separator pulse itself, the porch after it has the opposite tone.
+        let separator_range = division(separator_start, separator_start + spec.separator_time);
+        let separator = average(&data_to_parse[separator_range.clone()]);
+        self.report_tone(
+            Tone::Separator,
+            separator_range.start,
+            &data_to_parse[separator_range],
+        );
+        let chroma_index = if separator < 1900.0 { 0 } else { 1 };
+        // Only the separator of a R-Y line is black.
+        let black = if chroma_index == 0 {
+            (separator_start, spec.separator_time)
+        } else {
+            (spec.sync_time, spec.porch_time)
+        };
+        self.measure_levels((0.0, spec.sync_time), black);
+        self.robot_chroma[chroma_index] = line_chroma;
+
+        if !self.robot_previous_y.is_empty() && self.counter > 0 {
+            for i in 0..pixel_count {
+                self.picture[self.counter - 1][i] = ycrcb_to_rgb(
+                    self.robot_previous_y[i],
+                    self.robot_chroma[0][i],
+                    self.robot_chroma[1][i],
+                );
             }
-            img.save("pic.png")
-                .unwrap_or_else(|x| println!("Store failed. {:?}", x));
+            self.line_decoded(self.counter - 1);
+        }
+        for (i, &y) in line_y.iter().enumerate() {
+            self.picture[self.counter][i] =
+                ycrcb_to_rgb(y, self.robot_chroma[0][i], self.robot_chroma[1][i]);
+        }
+        self.line_decoded(self.counter);
+
+        // A B-Y line closes the pair, the next R-Y line starts a new one.
+        self.robot_previous_y = if chroma_index == 0 { line_y } else { vec![] };
+
+        self.pop_samples_in_ms(line_time);
+        self.counter += 1;
+    }
+
+    ///
+    /// Robot 72 and Robot 24 modes
+    ///
+    /// Scan Sequence: Sync, Porch, Y, Separator, Porch, R-Y, Separator, Porch, B-Y
+    ///
+    fn decode_in_robot72(&mut self, spec: &ModeSpec, line_time: f64) {
+        let pixel_count = self.picture[self.counter].len();
+        let y_time = spec.scan_time(Channel::Y);
+        let chroma_time = spec.scan_time(Channel::RY);
+        let separator_time = spec.separator_time + spec.separator_porch_time;
+
+        // sync-porch-y-separator-porch-ry-separator-porch-by
+        let y_start = spec.sync_time + spec.porch_time;
+        let ry_start = y_start + y_time + separator_time;
+        let by_start = ry_start + chroma_time + separator_time;
+
+        // The separator before the R-Y scan is black.
+        self.measure_levels(
+            (0.0, spec.sync_time),
+            (y_start + y_time, spec.separator_time),
+        );
+        let data_to_parse = self.get_line_data(line_time);
+        let division = |start: f64, end: f64| {
+            self.ms_to_samples(start).min(data_to_parse.len())
+                ..self.ms_to_samples(end).min(data_to_parse.len())
+        };
 
-            println!("Finish Decoding!");
+        let line_y = self.decode_line_info(
+            &data_to_parse[division(y_start, y_start + y_time)],
+            pixel_count,
+        );
+        let line_ry = self.decode_line_info(
+            &data_to_parse[division(ry_start, ry_start + chroma_time)],
+            pixel_count,
+        );
+        let line_by = self.decode_line_info(
+            &data_to_parse[division(by_start, by_start + chroma_time)],
+            pixel_count,
+        );
+
+        for i in 0..pixel_count {
+            self.picture[self.counter][i] = ycrcb_to_rgb(line_y[i], line_ry[i], line_by[i]);
         }
+        self.line_decoded(self.counter);
 
-        let time = 121.6 * 4.0 + 20.0 + 2.08;
-        let pixel_count = 640;
-        let sample_count = (time * get_sample_length_in_ms(self.sample_rate) as f32) as usize;
-        if self.sample_queue.len() < sample_count {
-            eprintln!("Error: PCM data length is not equal to a PD120 line.");
-            return;
+        self.pop_samples_in_ms(line_time);
+        self.counter += 1;
+    }
+
+    ///
+    /// Robot black and white modes
+    ///
+    /// Scan Sequence: Sync, Porch, Y
+    ///
+    /// The Y is the brightness over the whole 1500-2300hz range, so it becomes the gray
+    /// pixel as it is.
+    ///
+    fn decode_in_robot_bw(&mut self, spec: &ModeSpec, line_time: f64) {
+        let pixel_count = self.picture[self.counter].len();
+
+        // sync-porch-y
+        let y_start = spec.sync_time + spec.porch_time;
+        let y_end = y_start + spec.scan_time(Channel::Y);
+
+        self.measure_levels((0.0, spec.sync_time), (spec.sync_time, spec.porch_time));
+        let data_to_parse = self.get_line_data(line_time);
+        let division = self.ms_to_samples(y_start).min(data_to_parse.len())
+            ..self.ms_to_samples(y_end).min(data_to_parse.len());
+
+        let line_y = self.decode_line_info(&data_to_parse[division], pixel_count);
+        for (pixel, y) in self.picture[self.counter].iter_mut().zip(line_y) {
+            *pixel = [y; 3];
         }
+        self.line_decoded(self.counter);
 
-        println!("line {} decoding", self.counter);
+        self.pop_samples_in_ms(line_time);
+        self.counter += 1;
+    }
 
-        let data_to_parse = &self
-            .sample_queue
-            .range(0..sample_count)
-            .cloned()
-            .collect::<Vec<f32>>();
-
-        // 0-20-22.08-143.58-265.28-386.88-end
-        let division = [
-            0,
-            (20.0 * get_sample_length_in_ms(self.sample_rate)) as usize,
-            (22.08 * get_sample_length_in_ms(self.sample_rate)) as usize,
-            (143.58 * get_sample_length_in_ms(self.sample_rate)) as usize,
-            (265.28 * get_sample_length_in_ms(self.sample_rate)) as usize,
-            (386.88 * get_sample_length_in_ms(self.sample_rate)) as usize,
-            data_to_parse.len(),
-        ];
-
-        //  let _sync = self.decoder(&data_to_parse[division[0]..division[1]], true);
-        //  let _porch = self.decoder(&data_to_parse[division[1]..division[2]], true);
-
-        let line_y1 = self.decode_line_info(&data_to_parse[division[2]..division[3]], pixel_count);
-        let line_ry = self.decode_line_info(&data_to_parse[division[3]..division[4]], pixel_count);
-        let line_by = self.decode_line_info(&data_to_parse[division[4]..division[5]], pixel_count);
-        let line_y2 = self.decode_line_info(&data_to_parse[division[5]..division[6]], pixel_count);
+    ///
+    /// PD modes
+    ///
+    /// Scan Sequence: Sync, Porch, Y (odd line), R-Y, B-Y, Y (even line)
+    ///
+    /// Each sync pulse carries two lines of the picture, sharing the averaged chroma.
+    ///
+    fn decode_in_pd(&mut self, spec: &ModeSpec, time: f64) {
+        let scan_time = spec.scan_time(Channel::Y);
+        let pixel_count = self.picture[self.counter].len();
+
+        self.measure_levels((0.0, spec.sync_time), (spec.sync_time, spec.porch_time));
+        let data_to_parse = &self.get_line_data(time);
+        // sync-porch-y1-ry-by-y2
+        let y1_start = spec.sync_time + spec.porch_time;
+        let division: Vec<usize> = (0..5)
+            .map(|i| {
+                self.ms_to_samples(y1_start + scan_time * i as f64)
+                    .min(data_to_parse.len())
+            })
+            .collect();
+
+        let line_y1 = self.decode_line_info(&data_to_parse[division[0]..division[1]], pixel_count);
+        let line_ry = self.decode_line_info(&data_to_parse[division[1]..division[2]], pixel_count);
+        let line_by = self.decode_line_info(&data_to_parse[division[2]..division[3]], pixel_count);
+        let line_y2 = self.decode_line_info(&data_to_parse[division[3]..division[4]], pixel_count);
 
         for i in 0..pixel_count {
-            let by_minus_128 = line_by[i] as f32 - 128.0;
-            let ry_minus_128 = line_ry[i] as f32 - 128.0;
-            let y1_minus_16_mul_298_082 = (line_y1[i] as f32 - 16.0) * 298.082;
-            let y2_minus_16_mul_298_082 = (line_y2[i] as f32 - 16.0) * 298.082;
-            self.picture[self.counter][i] = [
-                (0.003906 * (y1_minus_16_mul_298_082 + 408.583 * ry_minus_128)) as u8,
-                (0.003906
-                    * (y1_minus_16_mul_298_082 + -100.291 * by_minus_128 + -208.12 * ry_minus_128))
-                    as u8,
-                (0.003906 * (y1_minus_16_mul_298_082 + 516.411 * by_minus_128)) as u8,
-            ];
-            self.picture[self.counter + 1][i] = [
-                (0.003906 * (y2_minus_16_mul_298_082 + 408.583 * ry_minus_128)) as u8,
-                (0.003906
-                    * (y2_minus_16_mul_298_082 + -100.291 * by_minus_128 + -208.12 * ry_minus_128))
-                    as u8,
-                (0.003906 * (y2_minus_16_mul_298_082 + 516.411 * by_minus_128)) as u8,
-            ];
+            self.picture[self.counter][i] = ycrcb_to_rgb(line_y1[i], line_ry[i], line_by[i]);
+            self.picture[self.counter + 1][i] = ycrcb_to_rgb(line_y2[i], line_ry[i], line_by[i]);
         }
+        self.line_decoded(self.counter);
+        self.line_decoded(self.counter + 1);
 
+        self.pop_samples_in_ms(time);
         self.counter += 2;
     }
 }
+
+/// Frequency of the sync pulses.
+const SYNC_FREQUENCY: f32 = 1200.0;
+
+/// Frequency of the black, also used by the porches.
+const BLACK_FREQUENCY: f32 = 1500.0;
+
+/// Frequency of the white.
+const WHITE_FREQUENCY: f32 = 2300.0;
+
+/// How far the tones of the VIS header could be from their frequency, when the receiver
+/// is mistuned. The 1100hz bits stay above the 1000hz edge of the bandpass filter.
+const MISTUNING_TOLERANCE: f32 = 150.0;
+
+/// Time skipped at both edges of a pulse when measuring its level.
+const LEVEL_EDGE_TIME: f64 = 1.5;
+
+/// Shortest time left to measure a pulse, after skipping the edges.
+const LEVEL_STEADY_TIME: f64 = 1.5;
+
+/// How much a line moves the measured sync and black levels.
+const LEVEL_SMOOTHING: f32 = 0.1;
+
+/// Silence added by [`SSTVDecoder::finish`] after the delay of the Hilbert transform,
+/// for the bandpass filter and the rounding of the line timing, in ms.
+const FINISH_PADDING_TIME: f64 = 20.0;
+
+/// Convert a [Y, R-Y, B-Y] pixel back into [R, G, B].
+///
+/// Refrence: Dayton Paper Appendix B: YRyBy (YCrCb) Color Encoding
+fn ycrcb_to_rgb(y: u8, ry: u8, by: u8) -> [u8; 3] {
+    let by_minus_128 = by as f32 - 128.0;
+    let ry_minus_128 = ry as f32 - 128.0;
+    let y_minus_16_mul_298_082 = (y as f32 - 16.0) * 298.082;
+    [
+        (0.003906 * (y_minus_16_mul_298_082 + 408.583 * ry_minus_128)) as u8,
+        (0.003906 * (y_minus_16_mul_298_082 + -100.291 * by_minus_128 + -208.12 * ry_minus_128))
+            as u8,
+        (0.003906 * (y_minus_16_mul_298_082 + 516.411 * by_minus_128)) as u8,
+    ]
+}
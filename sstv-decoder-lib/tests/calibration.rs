@@ -0,0 +1,72 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use sstv_decoder_lib::{
+    Mode, SSTVImage,
+    sstv_decoder::{DecoderEvent, SSTVDecoder},
+};
+use sstv_encoder_lib::{EncoderConfig, ToneLabel, ToneSequence, encode_picture_into_tones};
+
+use common::{SAMPLE_RATE, decode_in_chunks, decoded_image};
+
+const GRAY: u8 = 128;
+
+/// The signal of a gray picture, with every tone moved by the offset in hz, as sent to a
+/// mistuned receiver.
+fn mistuned_signal(offset: f32) -> Vec<f32> {
+    let image = SSTVImage::new(160, 120, vec![[GRAY; 3]; 160 * 120]).unwrap();
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    let tones = encode_picture_into_tones(&config, &image, &Mode::Robot8Bw).unwrap();
+    let mut mistuned = ToneSequence::new();
+    for tone in tones.tones() {
+        match tone.label {
+            ToneLabel::Silence => mistuned.push_silence(tone.duration),
+            label => mistuned.push(tone.frequency + offset, tone.duration, label),
+        }
+    }
+    mistuned.render::<f32>(&config).unwrap()
+}
+
+/// The average brightness of the decoded picture.
+fn decoded_brightness(offset: f32, calibration: bool) -> f32 {
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    decoder.set_calibration(calibration);
+    let events = decode_in_chunks(&mut decoder, &mistuned_signal(offset), 1024);
+    assert!(
+        events.iter().any(|event| matches!(
+            event,
+            DecoderEvent::VisDecoded {
+                mode: Mode::Robot8Bw,
+                ..
+            }
+        )),
+        "{offset}hz: {events:?}"
+    );
+    let image = decoded_image(&events).expect("a picture");
+    let sum: u64 = (0..image.get_height())
+        .flat_map(|y| (0..image.get_width()).map(move |x| (x, y)))
+        .map(|(x, y)| image.get_rgb_pixel(x, y)[0] as u64)
+        .sum();
+    sum as f32 / (image.get_width() * image.get_height()) as f32
+}
+
+#[test]
+fn calibration_corrects_a_mistuned_signal() {
+    for offset in [-100.0, -55.0, 55.0, 100.0] {
+        let calibrated = decoded_brightness(offset, true);
+        assert!(
+            (calibrated - GRAY as f32).abs() < 6.0,
+            "{offset}hz: {calibrated} with calibration"
+        );
+
+        // 800hz is the whole 0-255 range.
+        let shift = offset / 800.0 * 255.0;
+        let uncalibrated = decoded_brightness(offset, false);
+        assert!(
+            (uncalibrated - (GRAY as f32 + shift)).abs() < 6.0,
+            "{offset}hz: {uncalibrated} without calibration"
+        );
+    }
+}
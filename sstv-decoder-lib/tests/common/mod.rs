@@ -0,0 +1,79 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+#![allow(dead_code)]
+
+use sstv_decoder_lib::{
+    Mode, SSTVImage, Sample,
+    sstv_decoder::{DecoderEvent, SSTVDecoder},
+};
+use sstv_encoder_lib::{EncoderConfig, SSTVEncoder};
+
+pub const SAMPLE_RATE: u32 = 11025;
+
+/// White, yellow, cyan, green, magenta, red, blue and black bars.
+pub fn colour_bars() -> SSTVImage {
+    const BARS: [[u8; 3]; 8] = [
+        [255, 255, 255],
+        [255, 255, 0],
+        [0, 255, 255],
+        [0, 255, 0],
+        [255, 0, 255],
+        [255, 0, 0],
+        [0, 0, 255],
+        [0, 0, 0],
+    ];
+    let (width, height) = (320, 256);
+    let pixels = (0..width * height)
+        .map(|i| BARS[i % width * BARS.len() / width])
+        .collect();
+    SSTVImage::new(width, height, pixels).unwrap()
+}
+
+/// The transmission of the colour bars in the mode, without any silence.
+pub fn transmission(mode: Mode) -> Vec<f32> {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    SSTVEncoder::new(&config, &colour_bars(), &mode)
+        .unwrap()
+        .collect()
+}
+
+pub fn ms_to_samples(time: usize) -> usize {
+    time * SAMPLE_RATE as usize / 1000
+}
+
+/// Give the samples to the decoder in chunks of `chunk_size`, then finish the stream,
+/// return all the events.
+pub fn decode_in_chunks<S: Sample>(
+    decoder: &mut SSTVDecoder,
+    samples: &[S],
+    chunk_size: usize,
+) -> Vec<DecoderEvent> {
+    let mut events: Vec<DecoderEvent> = samples
+        .chunks(chunk_size)
+        .flat_map(|chunk| decoder.decode(chunk).unwrap())
+        .collect();
+    events.extend(decoder.finish());
+    events
+}
+
+/// The picture of the first ImageComplete event.
+pub fn decoded_image(events: &[DecoderEvent]) -> Option<&SSTVImage> {
+    events.iter().find_map(|event| match event {
+        DecoderEvent::ImageComplete { image, .. } => Some(image),
+        _ => None,
+    })
+}
+
+/// Mean absolute difference of the channels of two pictures of the same size.
+pub fn mean_error(decoded: &SSTVImage, expected: &SSTVImage) -> f64 {
+    let (width, height) = (decoded.get_width(), decoded.get_height());
+    let mut error = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let [a, b] = [decoded, expected].map(|image| image.get_rgb_pixel(x, y));
+            error += (0..3).map(|c| a[c].abs_diff(b[c]) as u64).sum::<u64>();
+        }
+    }
+    error as f64 / (width * height * 3) as f64
+}
@@ -0,0 +1,79 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use sstv_decoder_lib::{
+    Mode,
+    diagnostics::{CsvDiagnostics, Diagnostics, MemoryDiagnostics, Tone},
+    sstv_decoder::SSTVDecoder,
+};
+
+use common::{SAMPLE_RATE, decode_in_chunks, decoded_image, transmission};
+
+/// The shortest mode, so the whole picture could be decoded quickly.
+const MODE: Mode = Mode::Robot8Bw;
+
+/// Decode the samples with the diagnostics, and return it.
+fn decode_with<D: Diagnostics + Send + 'static>(samples: &[f32], diagnostics: D) -> D {
+    let diagnostics = Arc::new(Mutex::new(diagnostics));
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    decoder.set_diagnostics(diagnostics.clone());
+    let events = decode_in_chunks(&mut decoder, samples, 1024);
+    assert!(decoded_image(&events).is_some(), "{events:?}");
+    drop(decoder);
+    Arc::into_inner(diagnostics).unwrap().into_inner().unwrap()
+}
+
+#[test]
+fn memory_diagnostics_follow_the_transmission() {
+    let samples = transmission(MODE);
+    let diagnostics = decode_with(&samples, MemoryDiagnostics::default());
+
+    // Every sample comes out of the filters once the stream is finished, followed by a
+    // part of the padding.
+    let frequencies = diagnostics.frequencies.len();
+    assert!(frequencies >= samples.len(), "{frequencies} frequencies");
+    assert!(frequencies < samples.len() + SAMPLE_RATE as usize / 2);
+
+    let tones: Vec<Tone> = diagnostics.tones.iter().map(|tone| tone.tone).collect();
+    assert_eq!(tones[..3], [Tone::Leader, Tone::Break, Tone::Leader]);
+    assert_eq!(tones[3..13], (0..10).map(Tone::VisBit).collect::<Vec<_>>());
+    for (tone, frequency) in diagnostics.tones[..3].iter().zip([1900.0, 1200.0, 1900.0]) {
+        assert!((tone.frequency - frequency).abs() < 50.0, "{tone:?}");
+    }
+    assert!(
+        diagnostics
+            .tones
+            .windows(2)
+            .all(|pair| pair[0].position < pair[1].position)
+    );
+
+    let lines: Vec<usize> = diagnostics.lines.iter().map(|line| line.index).collect();
+    assert_eq!(lines, (0..MODE.spec().height).collect::<Vec<_>>());
+}
+
+#[test]
+fn csv_diagnostics_write_a_row_for_everything() {
+    let samples = transmission(MODE);
+    let memory = decode_with(&samples, MemoryDiagnostics::default());
+    let mut csv = decode_with(&samples, CsvDiagnostics::new(vec![], vec![]));
+    csv.flush().unwrap();
+    let (frequency_csv, event_csv) = csv.into_inner();
+
+    let frequency_csv = String::from_utf8(frequency_csv).unwrap();
+    let rows: Vec<&str> = frequency_csv.lines().collect();
+    assert_eq!(rows[0], "sample,frequency");
+    assert_eq!(rows.len(), 1 + memory.frequencies.len());
+    assert_eq!(rows[1], format!("0,{:.2}", memory.frequencies[0]));
+
+    let event_csv = String::from_utf8(event_csv).unwrap();
+    let rows: Vec<&str> = event_csv.lines().collect();
+    assert_eq!(rows[0], "sample,length,kind,value");
+    assert_eq!(rows.len(), 1 + memory.tones.len() + memory.lines.len());
+    assert!(rows[1].contains(",Leader,"), "{}", rows[1]);
+    let line_rows = rows.iter().filter(|row| row.contains(",Line,")).count();
+    assert_eq!(line_rows, MODE.spec().height);
+}
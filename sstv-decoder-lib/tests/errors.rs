@@ -0,0 +1,107 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use sstv_decoder_lib::{
+    DecodeError, Mode,
+    sstv_decoder::{DecoderEvent, DecoderState, SSTVDecoder},
+};
+use sstv_encoder_lib::{EncoderConfig, ToneLabel, ToneSequence, encode_picture_into_tones};
+
+use common::{SAMPLE_RATE, colour_bars, decode_in_chunks, decoded_image, transmission};
+
+/// The transmission of Robot 8 B/W with other bits in the VIS code, least significant bit
+/// first, and the parity bit.
+fn transmission_with_vis_bits(bits: [bool; 7], parity: bool) -> Vec<f32> {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    let tones = encode_picture_into_tones(&config, &colour_bars(), &Mode::Robot8Bw).unwrap();
+    let bit_frequency = |bit: bool| if bit { 1100.0 } else { 1300.0 };
+    let mut bits = bits.into_iter();
+    let mut changed = ToneSequence::new();
+    for tone in tones.tones() {
+        let frequency = match tone.label {
+            ToneLabel::VisBit => bit_frequency(bits.next().unwrap()),
+            ToneLabel::VisParity => bit_frequency(parity),
+            _ => tone.frequency,
+        };
+        changed.push(frequency, tone.duration, tone.label);
+    }
+    changed.render::<f32>(&config).unwrap()
+}
+
+fn aborted(events: &[DecoderEvent]) -> Vec<DecodeError> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            DecoderEvent::Aborted(e) => Some(e.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn sample_rate_too_low_for_the_bandpass_filter() {
+    // 3000hz needs more than 6000hz.
+    for sample_rate in [0.0, 6000.0, f32::INFINITY] {
+        assert_eq!(
+            SSTVDecoder::new(sample_rate).err(),
+            Some(DecodeError::InvalidSampleRate(sample_rate))
+        );
+    }
+    assert!(matches!(
+        SSTVDecoder::new(f32::NAN).err(),
+        Some(DecodeError::InvalidSampleRate(sample_rate)) if sample_rate.is_nan()
+    ));
+
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    assert_eq!(
+        decoder.switch_sample(4000.0),
+        Err(DecodeError::InvalidSampleRate(4000.0))
+    );
+    assert_eq!(decoder.sample_rate(), SAMPLE_RATE as f32);
+}
+
+#[test]
+fn samples_which_are_not_finite_are_ignored() {
+    let samples = transmission(Mode::Robot8Bw);
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    assert_eq!(
+        decoder.decode(&[0.0, 0.5, f32::NAN]).err(),
+        Some(DecodeError::InvalidSample(2))
+    );
+    assert_eq!(
+        decoder.decode(&[f32::NEG_INFINITY]).err(),
+        Some(DecodeError::InvalidSample(0))
+    );
+
+    // The filters are not broken by the ignored data.
+    let events = decode_in_chunks(&mut decoder, &samples, 1024);
+    assert!(decoded_image(&events).is_some(), "{events:?}");
+}
+
+#[test]
+fn wrong_parity_of_the_vis_code() {
+    // Robot 8 B/W is 2, with a single 1 bit, so the parity bit should be 1.
+    let bits = [false, true, false, false, false, false, false];
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    let events = decode_in_chunks(&mut decoder, &transmission_with_vis_bits(bits, false), 1024);
+    assert_eq!(aborted(&events), [DecodeError::VisParity(2)]);
+    assert!(decoded_image(&events).is_none());
+    assert_eq!(decoder.state(), DecoderState::Idle);
+
+    let events = decode_in_chunks(&mut decoder, &transmission_with_vis_bits(bits, true), 1024);
+    assert_eq!(aborted(&events), []);
+    assert!(decoded_image(&events).is_some());
+}
+
+#[test]
+fn unknown_vis_code() {
+    let bits = [true, false, false, false, false, false, false];
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    let events = decode_in_chunks(&mut decoder, &transmission_with_vis_bits(bits, true), 1024);
+    assert_eq!(Mode::from_vis_code(1), None);
+    assert_eq!(aborted(&events), [DecodeError::UnknownVisCode(1)]);
+    assert!(decoded_image(&events).is_none());
+    assert_eq!(decoder.state(), DecoderState::Idle);
+}
@@ -0,0 +1,69 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use sstv_decoder_lib::{
+    Mode,
+    sstv_decoder::{DecoderEvent, SSTVDecoder},
+};
+
+use common::{SAMPLE_RATE, decode_in_chunks, decoded_image, ms_to_samples, transmission};
+
+/// The shortest mode, so the whole picture could be decoded quickly.
+const MODE: Mode = Mode::Robot8Bw;
+
+fn vis_decoded(events: &[DecoderEvent]) -> bool {
+    events
+        .iter()
+        .any(|event| matches!(event, DecoderEvent::VisDecoded { mode: MODE, .. }))
+}
+
+#[test]
+fn noise_before_the_leader() {
+    let mut seed: u32 = 12345;
+    let mut samples: Vec<f32> = (0..SAMPLE_RATE * 3 / 2)
+        .map(|_| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            ((seed >> 16) as f32 / 32768.0 - 1.0) * 0.5
+        })
+        .collect();
+    samples.extend(transmission(MODE));
+
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    let events = decode_in_chunks(&mut decoder, &samples, 1024);
+    assert!(vis_decoded(&events), "{events:?}");
+    assert!(decoded_image(&events).is_some());
+}
+
+#[test]
+fn leader_split_across_chunks() {
+    let samples = transmission(MODE);
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    let mut events = vec![];
+    let mut rest = &samples[..];
+    // Chunks much shorter than the leader tone, of changing sizes.
+    for size in [1, 37, 3, 512, 99].into_iter().cycle() {
+        if rest.is_empty() {
+            break;
+        }
+        let (chunk, left) = rest.split_at(size.min(rest.len()));
+        events.extend(decoder.decode(chunk).unwrap());
+        rest = left;
+    }
+    events.extend(decoder.finish());
+    assert!(vis_decoded(&events), "{events:?}");
+    assert!(decoded_image(&events).is_some());
+}
+
+#[test]
+fn recording_starts_in_the_middle_of_the_leader() {
+    let samples = transmission(MODE);
+    // Down to a few ms of the first leader tone, which is 300ms long.
+    for cut in [150, 290, 295, 299] {
+        let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+        let events = decode_in_chunks(&mut decoder, &samples[ms_to_samples(cut)..], 1024);
+        assert!(vis_decoded(&events), "cut at {cut}ms: {events:?}");
+        assert!(decoded_image(&events).is_some(), "cut at {cut}ms");
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use sstv_decoder_lib::{
+    ColorModel, I24, Mode, SSTVImage, Sample,
+    sstv_decoder::{DecoderEvent, DecoderState, SSTVDecoder},
+};
+use sstv_encoder_lib::{EncoderConfig, encode_picture_into_pcm};
+
+use common::{SAMPLE_RATE, colour_bars, decode_in_chunks, decoded_image, mean_error};
+
+/// What the picture should look like after the mode, in [R, G, B].
+fn expected_picture(image: &SSTVImage, mode: Mode) -> SSTVImage {
+    let spec = mode.spec();
+    let image = image.resize_image(spec.width, spec.height);
+    if spec.color_model != ColorModel::Monochrome {
+        return image;
+    }
+    let pixels = (0..spec.width * spec.height)
+        .map(|i| [image.get_luminance(i % spec.width, i / spec.width); 3])
+        .collect();
+    SSTVImage::new(spec.width, spec.height, pixels).unwrap()
+}
+
+#[test]
+fn every_mode_decodes_its_own_signal() {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    for mode in Mode::all() {
+        let samples: Vec<f32> = encode_picture_into_pcm(&config, &colour_bars(), &mode).unwrap();
+
+        let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+        let events = decode_in_chunks(&mut decoder, &samples, 997);
+        assert!(
+            events.iter().any(|event| matches!(
+                event,
+                DecoderEvent::VisDecoded { mode: decoded, .. } if *decoded == mode
+            )),
+            "{mode}: {events:?}"
+        );
+        let image = decoded_image(&events).unwrap_or_else(|| panic!("{mode}: no picture"));
+        let error = mean_error(image, &expected_picture(&colour_bars(), mode));
+        // The edges of the bars are smeared by the filters, and the saturated colours
+        // are clipped by the YCrCb modes.
+        assert!(error < 20.0, "{mode}: mean error {error:.2}");
+    }
+}
+
+#[test]
+fn decoded_picture_is_sent_again() {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    let decode = |image: &SSTVImage| {
+        let samples: Vec<f32> = encode_picture_into_pcm(&config, image, &Mode::Martin1).unwrap();
+        let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+        let events = decode_in_chunks(&mut decoder, &samples, 4096);
+        decoded_image(&events).cloned().expect("a picture")
+    };
+
+    let received = decode(&colour_bars());
+    let received_again = decode(&received);
+    let error = mean_error(&received_again, &received);
+    assert!(error < 10.0, "mean error {error:.2}");
+}
+
+#[test]
+fn last_lines_are_decoded_when_the_stream_is_finished() {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    for mode in [Mode::Robot8Bw, Mode::Martin1] {
+        // The stream ends right after the last line, without any silence.
+        let samples: Vec<f32> = encode_picture_into_pcm(&config, &colour_bars(), &mode).unwrap();
+        let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+        for chunk in samples.chunks(4096) {
+            decoder.decode(chunk).unwrap();
+        }
+        let height = mode.spec().height;
+        assert!(
+            matches!(decoder.state(), DecoderState::Receiving { line, .. } if line < height),
+            "{mode}: {:?}",
+            decoder.state()
+        );
+
+        let events = decoder.finish();
+        assert!(decoded_image(&events).is_some(), "{mode}: {events:?}");
+        assert_eq!(decoder.state(), DecoderState::Complete { mode });
+    }
+}
+
+#[test]
+fn integer_samples_decode_as_float_samples() {
+    fn decode<S: Sample>(samples: &[S]) -> SSTVImage {
+        let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+        let events = decode_in_chunks(&mut decoder, samples, 997);
+        decoded_image(&events).cloned().expect("a picture")
+    }
+    fn encode<S: Sample>(mode: Mode) -> Vec<S> {
+        let config = EncoderConfig::new(SAMPLE_RATE);
+        encode_picture_into_pcm(&config, &colour_bars(), &mode).unwrap()
+    }
+
+    for mode in [Mode::Robot36, Mode::Martin1] {
+        let expected = decode(&encode::<f32>(mode));
+        // Only a few bits are left for the signal in u8 samples.
+        for (image, max_error) in [
+            (decode(&encode::<u8>(mode)), 4.0),
+            (decode(&encode::<i16>(mode)), 0.1),
+            (decode(&encode::<I24>(mode)), 0.1),
+        ] {
+            let error = mean_error(&image, &expected);
+            assert!(error < max_error, "{mode}: mean error {error:.2}");
+        }
+
+        // u8 samples are unsigned, centred on 128.
+        let samples = encode::<u8>(mode);
+        let centred: Vec<f32> = samples
+            .iter()
+            .map(|sample| (*sample as f32 - 128.0) / 128.0)
+            .collect();
+        assert_eq!(decode(&samples), decode(&centred), "{mode}");
+    }
+}
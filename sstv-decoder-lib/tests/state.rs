@@ -0,0 +1,156 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use std::mem::discriminant;
+
+use sstv_decoder_lib::{
+    DecodeError, Mode,
+    sstv_decoder::{DecoderEvent, DecoderState, SSTVDecoder},
+};
+use sstv_encoder_lib::{EncoderConfig, SSTVEncoder};
+
+use common::{SAMPLE_RATE, colour_bars, decoded_image, ms_to_samples, transmission};
+
+const CHUNK_SIZE: usize = 256;
+
+#[test]
+fn states_follow_the_transmission() {
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    let mut states = vec![decoder.state()];
+    for chunk in transmission(Mode::Robot8Bw).chunks(CHUNK_SIZE) {
+        decoder.decode(chunk).unwrap();
+        let state = decoder.state();
+        if discriminant(&state) != discriminant(states.last().unwrap()) {
+            states.push(state);
+        }
+    }
+    decoder.finish();
+    states.push(decoder.state());
+    assert_eq!(
+        states,
+        [
+            DecoderState::Idle,
+            DecoderState::Leader,
+            DecoderState::Vis,
+            DecoderState::Receiving {
+                mode: Mode::Robot8Bw,
+                line: 0
+            },
+            DecoderState::Complete {
+                mode: Mode::Robot8Bw
+            },
+        ]
+    );
+}
+
+#[test]
+fn pd_progress_moves_two_lines_at_a_time() {
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    assert_eq!(decoder.progress(), None);
+    let mut lines_done = vec![];
+    for chunk in transmission(Mode::Pd50).chunks(CHUNK_SIZE) {
+        decoder.decode(chunk).unwrap();
+        if let Some(progress) = decoder.progress() {
+            assert_eq!(progress.mode, Mode::Pd50);
+            assert_eq!(progress.lines_total, 256);
+            assert!(progress.elapsed <= progress.expected);
+            lines_done.push(progress.lines_done);
+        }
+    }
+    decoder.finish();
+    lines_done.dedup();
+    assert_eq!(lines_done, (0..256).step_by(2).collect::<Vec<_>>());
+    assert_eq!(decoder.progress(), None);
+    assert_eq!(decoder.state(), DecoderState::Complete { mode: Mode::Pd50 });
+}
+
+#[test]
+fn abort_finds_the_header_already_received() {
+    // The line of PD290 is longer than a VIS header, so a whole header fits in the queue.
+    let first: Vec<f32> = SSTVEncoder::new(
+        &EncoderConfig::new(SAMPLE_RATE),
+        &colour_bars(),
+        &Mode::Pd290,
+    )
+    .unwrap()
+    .take(ms_to_samples(1000))
+    .collect();
+    let second = transmission(Mode::Robot8Bw);
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+
+    // The VIS header of PD290 and a part of its first line.
+    let events = decoder.decode(&first).unwrap();
+    assert!(matches!(
+        events.last(),
+        Some(DecoderEvent::VisDecoded {
+            mode: Mode::Pd290,
+            ..
+        })
+    ));
+    // The header of the next picture, up to its start bit, without ending the PD290 line.
+    let (header, rest) = second.split_at(ms_to_samples(600));
+    assert!(decoder.decode(header).unwrap().is_empty());
+
+    decoder.abort();
+    assert_eq!(decoder.state(), DecoderState::Idle);
+    assert_eq!(decoder.progress(), None);
+
+    let mut events: Vec<DecoderEvent> = rest
+        .chunks(CHUNK_SIZE)
+        .flat_map(|chunk| decoder.decode(chunk).unwrap())
+        .collect();
+    events.extend(decoder.finish());
+    assert!(matches!(
+        events[..3],
+        [
+            DecoderEvent::Aborted(DecodeError::Aborted),
+            DecoderEvent::HeaderDetected,
+            DecoderEvent::VisDecoded {
+                mode: Mode::Robot8Bw,
+                ..
+            }
+        ]
+    ));
+    assert_eq!(decoded_image(&events).unwrap().get_width(), 160);
+
+    // Nothing is under decoding any more.
+    decoder.abort();
+    assert!(decoder.decode(&[0.0]).unwrap().is_empty());
+}
+
+#[test]
+fn reset_forgets_the_stream() {
+    let samples = transmission(Mode::Robot8Bw);
+    let decode_all = |decoder: &mut SSTVDecoder| {
+        let mut events: Vec<DecoderEvent> = samples
+            .chunks(CHUNK_SIZE)
+            .flat_map(|chunk| decoder.decode(chunk).unwrap())
+            .collect();
+        events.extend(decoder.finish());
+        decoded_image(&events).cloned().expect("a picture")
+    };
+    let expected = decode_all(&mut SSTVDecoder::new(SAMPLE_RATE as f32).unwrap());
+
+    // Half of the picture, then the pending event of an abort.
+    let mut decoder = SSTVDecoder::new(SAMPLE_RATE as f32).unwrap();
+    decoder.decode(&samples[..samples.len() / 2]).unwrap();
+    assert!(matches!(
+        decoder.state(),
+        DecoderState::Receiving {
+            mode: Mode::Robot8Bw,
+            ..
+        }
+    ));
+    decoder.abort();
+
+    decoder.reset();
+    assert_eq!(decoder.state(), DecoderState::Idle);
+    assert_eq!(decoder.progress(), None);
+    assert!(decoder.decode(&[0.0; 16]).unwrap().is_empty());
+
+    // The filters and the queue start over, so the picture is decoded as by a new decoder.
+    decoder.reset();
+    assert_eq!(decode_all(&mut decoder), expected);
+}
@@ -0,0 +1,52 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+//! Encode throughput, run with `cargo bench -p sstv-encoder-lib`.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use sstv_encoder_lib::{EncoderConfig, Mode, Modulation, encode_picture_into_pcm};
+
+use common::gradient;
+
+const SAMPLE_RATE: u32 = 48000;
+
+/// The best of the runs, so other processes disturb the result less.
+const RUNS: usize = 5;
+
+fn main() {
+    let image = gradient();
+    let mode = Mode::Pd290;
+    for (name, config) in [
+        ("computed sine", EncoderConfig::new(SAMPLE_RATE)),
+        (
+            "sine table",
+            EncoderConfig::new(SAMPLE_RATE).with_sine_table(true),
+        ),
+        (
+            "shaped",
+            EncoderConfig::new(SAMPLE_RATE).with_modulation(Modulation::Shaped),
+        ),
+    ] {
+        let mut sample_count = 0;
+        let best = (0..RUNS)
+            .map(|_| {
+                let start = Instant::now();
+                let samples = encode_picture_into_pcm::<i16>(&config, &image, &mode).unwrap();
+                sample_count = black_box(samples).len();
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or(Duration::ZERO);
+        println!(
+            "{mode} at {SAMPLE_RATE}hz into i16, {name}: {best:.1?}, {:.1} Msamples/s",
+            sample_count as f64 / best.as_secs_f64() / 1e6
+        );
+    }
+}
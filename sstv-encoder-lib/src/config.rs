@@ -0,0 +1,148 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use std::time::Duration;
+
+use crate::EncodeError;
+
+/// Highest frequency of the signal, the white level.
+const MAX_FREQUENCY: u32 = 2300;
+
+///
+/// How the frequency moves from one tone to the next.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Modulation {
+    /// Every tone has a constant frequency, and the frequency steps at the boundaries.
+    #[default]
+    Stepped,
+    /// The frequency glides between the tones over one pixel, which narrows the occupied
+    /// bandwidth at the cost of a little sharpness.
+    Shaped,
+}
+
+///
+/// How to generate the audio.
+///
+/// Build it from the default or [`EncoderConfig::new`], then change what you need
+/// with the `with_` methods.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncoderConfig {
+    sample_rate: u32,
+    amplitude_dbfs: f32,
+    leading_silence: Duration,
+    trailing_silence: Duration,
+    vis_header: bool,
+    sine_table: bool,
+    modulation: Modulation,
+}
+
+impl Default for EncoderConfig {
+    /// 44100hz at full scale, with the VIS header and without silence.
+    fn default() -> Self {
+        Self::new(44100)
+    }
+}
+
+impl EncoderConfig {
+    /// Generate the audio at the sample rate, with the rest as the default.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            amplitude_dbfs: 0.0,
+            leading_silence: Duration::ZERO,
+            trailing_silence: Duration::ZERO,
+            vis_header: true,
+            sine_table: false,
+            modulation: Modulation::Stepped,
+        }
+    }
+
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Peak amplitude in dBFS, 0 is the full scale.
+    pub fn with_amplitude_dbfs(mut self, amplitude_dbfs: f32) -> Self {
+        self.amplitude_dbfs = amplitude_dbfs;
+        self
+    }
+
+    /// Silence before the VIS header.
+    pub fn with_leading_silence(mut self, leading_silence: Duration) -> Self {
+        self.leading_silence = leading_silence;
+        self
+    }
+
+    /// Silence after the picture.
+    pub fn with_trailing_silence(mut self, trailing_silence: Duration) -> Self {
+        self.trailing_silence = trailing_silence;
+        self
+    }
+
+    /// Whether to send the VIS header before the picture.
+    pub fn with_vis_header(mut self, vis_header: bool) -> Self {
+        self.vis_header = vis_header;
+        self
+    }
+
+    /// Whether to look up the sine from a table instead of computing it, which is faster
+    /// and off by less than 1e-5 of the full scale.
+    pub fn with_sine_table(mut self, sine_table: bool) -> Self {
+        self.sine_table = sine_table;
+        self
+    }
+
+    /// How the frequency moves between the tones, stepped by default.
+    pub fn with_modulation(mut self, modulation: Modulation) -> Self {
+        self.modulation = modulation;
+        self
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn amplitude_dbfs(&self) -> f32 {
+        self.amplitude_dbfs
+    }
+
+    pub fn leading_silence(&self) -> Duration {
+        self.leading_silence
+    }
+
+    pub fn trailing_silence(&self) -> Duration {
+        self.trailing_silence
+    }
+
+    pub fn vis_header(&self) -> bool {
+        self.vis_header
+    }
+
+    pub fn sine_table(&self) -> bool {
+        self.sine_table
+    }
+
+    pub fn modulation(&self) -> Modulation {
+        self.modulation
+    }
+
+    /// Peak amplitude, 1 is the full scale.
+    pub(crate) fn amplitude(&self) -> f32 {
+        10f32.powf(self.amplitude_dbfs / 20.0)
+    }
+
+    /// Will return an error if the sample rate could not carry the 2300hz white level,
+    /// or the amplitude is above the full scale.
+    pub(crate) fn validate(&self) -> Result<(), EncodeError> {
+        if self.sample_rate <= MAX_FREQUENCY * 2 {
+            return Err(EncodeError::InvalidSampleRate(self.sample_rate));
+        }
+        if !self.amplitude_dbfs.is_finite() || self.amplitude_dbfs > 0.0 {
+            return Err(EncodeError::InvalidAmplitude(self.amplitude_dbfs));
+        }
+        Ok(())
+    }
+}
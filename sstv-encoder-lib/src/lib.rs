@@ -2,120 +2,174 @@
 //!
 //! sstv-encoder-lib is a library to convert images into audio using the Slow Scan Television algorithms.
 //!
-//! Currently only support outputing 16 bit unsigned integer pcm samples.
+//! The pcm samples could be in any [`Sample`] format: u8, i16, 24 bit [`I24`], i32 or f32.
 //!
-//! For more detail, see encode_picture_into_pcm and encode_picture_into_file.
+//! For more detail, see encode_picture_into_pcm and encode_picture_into_writer, both
+//! configured by EncoderConfig. The writer gets a WAV, Sun .au or raw pcm stream, as
+//! chosen by OutputFormat. SSTVEncoder generates the pcm data on demand instead, and
+//! encode_picture_into_tones gives the tones before they become pcm data.
 
+mod config;
 mod header;
 mod martin;
+mod output;
 mod pasokon;
 mod pd;
 mod robot36;
 mod robot72;
-pub mod sample_generator;
+mod robot_bw;
+mod sample_generator;
 mod schottie;
-pub mod sstv_image;
-mod warsse_sc2_180;
+mod shaping_filter;
+pub mod sstv_encoder;
+pub mod tone;
+mod warsse_sc2;
 
-use std::fs::File;
+pub use config::{EncoderConfig, Modulation};
+pub use output::OutputFormat;
+pub use sstv_core::{
+    Channel, ColorModel, EncodeError, Family, I24, MODE_SPECS, Mode, ModeSpec, ParseModeError,
+    Sample, SstvError, sstv_image,
+};
+pub use sstv_encoder::SSTVEncoder;
+pub use tone::{Tone, ToneLabel, ToneSequence};
+
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom, Write},
+};
 
 use header::generate_header;
-use robot36::encode_in_robot36;
-use sample_generator::SampleGenerator;
+use output::{write_bytes, write_error, write_samples};
+use sstv_core::nearest_sample;
+use sstv_encoder::encode_line;
 use sstv_image::SSTVImage;
-use thiserror::Error;
-use wav_io::writer::i16samples_to_file;
 
-use crate::{
-    martin::encode_in_martin, pasokon::encode_in_pasokon, pd::encode_in_pd,
-    robot72::encode_in_robot72, schottie::encode_in_schottie,
-    warsse_sc2_180::encode_in_warsse_sc2_180,
-};
+/// Generate pure pcm data in the sample format `S`.
+///
+/// Will return an error if the image is empty, or the config is invalid.
+pub fn encode_picture_into_pcm<S: Sample>(
+    config: &EncoderConfig,
+    image: &SSTVImage,
+    mode: &Mode,
+) -> Result<Vec<S>, EncodeError> {
+    Ok(SSTVEncoder::new(config, image, mode)?.render_samples())
+}
 
-#[derive(Error, Debug)]
-pub enum FileError {
-    #[error("Create output file error: {0}")]
-    CreateFileError(String),
+/// Turn the picture into tones, including the silence and the VIS header of the config.
+///
+/// Will return an error if the image is empty. Render the tones into pcm data with
+/// ToneSequence::render, at any sample rate.
+pub fn encode_picture_into_tones(
+    config: &EncoderConfig,
+    image: &SSTVImage,
+    mode: &Mode,
+) -> Result<ToneSequence, EncodeError> {
+    if image.get_width() == 0 || image.get_height() == 0 {
+        return Err(EncodeError::EmptyImage);
+    }
 
-    #[error("Write output file error: {0}")]
-    WriteFileError(String),
+    let spec = mode.spec();
+    let image = image.resize_image(spec.width, spec.height);
+    let mut tones = ToneSequence::new();
+    if !config.leading_silence().is_zero() {
+        tones.push_silence(config.leading_silence().as_secs_f64() * 1000.0);
+    }
+    if config.vis_header() {
+        generate_header(&mut tones, spec);
+    }
+    for line in 0..spec.line_count() {
+        encode_line(&mut tones, &image, spec, line);
+    }
+    if !config.trailing_silence().is_zero() {
+        tones.push_silence(config.trailing_silence().as_secs_f64() * 1000.0);
+    }
+    Ok(tones)
+}
+
+/// Encode the picture into the writer in the output format and the sample format `S`.
+///
+/// All formats are written while being generated. A WAV header needs the length of the
+/// data first, so the samples are counted from the timing of the mode beforehand. The length of a Sun .au file is left unknown, use
+/// encode_picture_into_seekable_writer to fill it in if the writer could seek.
+pub fn encode_picture_into_writer<S: Sample, W: Write>(
+    config: &EncoderConfig,
+    image: &SSTVImage,
+    mode: &Mode,
+    format: OutputFormat,
+    mut writer: W,
+) -> Result<(), EncodeError> {
+    let mut encoder = SSTVEncoder::<S>::new(config, image, mode)?;
+    let sample_count = match format {
+        OutputFormat::Wav => Some(sample_count(config, mode)),
+        OutputFormat::Raw | OutputFormat::Au => None,
+    };
+    write_bytes(
+        &mut writer,
+        &format.header::<S>(config.sample_rate(), sample_count),
+    )?;
+    let written = write_samples(&mut encoder, format, &mut writer)?;
+    debug_assert!(sample_count.is_none_or(|count| count == written));
+    write_bytes(&mut writer, &format.trailer::<S>(written))?;
+    writer.flush().map_err(write_error)
 }
 
-/// Support modes.
-pub enum Mode {
-    Scottie1,
-    Scottie2,
-    ScottieDx,
-    Martin1,
-    Martin2,
-    Robot36,
-    Robot72,
-    WrasseSc2_180,
-    P3,
-    P5,
-    P7,
-    Pd50,
-    Pd90,
-    Pd120,
-    Pd160,
-    Pd180,
-    Pd240,
-    Pd290,
+/// Number of samples of the transmission with the silence and the VIS header of the config.
+///
+/// Every tone ends at the [`nearest_sample`] to its time, so the last one ends at the
+/// nearest sample to the time of the whole transmission.
+fn sample_count(config: &EncoderConfig, mode: &Mode) -> usize {
+    let time = config.leading_silence().as_secs_f64() * 1000.0
+        + mode.spec().transmission_time(config.vis_header())
+        + config.trailing_silence().as_secs_f64() * 1000.0;
+    nearest_sample(time, config.sample_rate()) as usize
 }
 
-/// Generate pure pcm data.
-pub fn encode_picture_into_pcm(
-    ctx: &mut SampleGenerator,
+/// Encode the picture into the writer in the output format and the sample format `S`.
+///
+/// All formats are written while being generated, then the length inside the header is
+/// filled in by seeking back to the start of the header.
+pub fn encode_picture_into_seekable_writer<S: Sample, W: Write + Seek>(
+    config: &EncoderConfig,
     image: &SSTVImage,
     mode: &Mode,
-) -> Vec<i16> {
-    [
-        generate_header(ctx, mode),
-        match mode {
-            Mode::Scottie1 => encode_in_schottie(ctx, image, mode),
-            Mode::Scottie2 => encode_in_schottie(ctx, image, mode),
-            Mode::ScottieDx => encode_in_schottie(ctx, image, mode),
-            Mode::Martin1 => encode_in_martin(ctx, image, mode),
-            Mode::Martin2 => encode_in_martin(ctx, image, mode),
-            Mode::Robot36 => encode_in_robot36(ctx, image),
-            Mode::Robot72 => encode_in_robot72(ctx, image),
-            Mode::WrasseSc2_180 => encode_in_warsse_sc2_180(ctx, image),
-            Mode::P3 => encode_in_pasokon(ctx, image, mode),
-            Mode::P5 => encode_in_pasokon(ctx, image, mode),
-            Mode::P7 => encode_in_pasokon(ctx, image, mode),
-            Mode::Pd50 => encode_in_pd(ctx, image, mode),
-            Mode::Pd90 => encode_in_pd(ctx, image, mode),
-            Mode::Pd120 => encode_in_pd(ctx, image, mode),
-            Mode::Pd160 => encode_in_pd(ctx, image, mode),
-            Mode::Pd180 => encode_in_pd(ctx, image, mode),
-            Mode::Pd240 => encode_in_pd(ctx, image, mode),
-            Mode::Pd290 => encode_in_pd(ctx, image, mode),
-        },
-    ]
-    .concat()
+    format: OutputFormat,
+    mut writer: W,
+) -> Result<(), EncodeError> {
+    let mut encoder = SSTVEncoder::<S>::new(config, image, mode)?;
+    let start = writer.stream_position().map_err(write_error)?;
+    write_bytes(&mut writer, &format.header::<S>(config.sample_rate(), None))?;
+    let sample_count = write_samples(&mut encoder, format, &mut writer)?;
+    write_bytes(&mut writer, &format.trailer::<S>(sample_count))?;
+
+    let end = writer.stream_position().map_err(write_error)?;
+    writer.seek(SeekFrom::Start(start)).map_err(write_error)?;
+    write_bytes(
+        &mut writer,
+        &format.header::<S>(config.sample_rate(), Some(sample_count)),
+    )?;
+    writer.seek(SeekFrom::Start(end)).map_err(write_error)?;
+    writer.flush().map_err(write_error)
 }
 
-/// Encode the picture into a file.
-pub fn encode_picture_into_file(
+/// Encode the picture into a mono WAV file in the sample format `S`.
+pub fn encode_picture_into_file<S: Sample>(
     image: &SSTVImage,
     mode: &Mode,
     name: &str,
-    sample_rate: u32,
-) -> Result<File, FileError> {
+    config: &EncoderConfig,
+) -> Result<File, EncodeError> {
     match std::fs::File::create(name) {
         Ok(mut v) => {
-            let head = wav_io::new_header(sample_rate, 16, false, true);
-            let ctx = &mut SampleGenerator::new(8000);
-            let samples = encode_picture_into_pcm(ctx, image, mode);
-            let result = i16samples_to_file(&mut v, &head, &samples);
-            if result.is_err() {
-                return Err(FileError::WriteFileError(result.err().unwrap().to_string()));
-            }
-
+            encode_picture_into_seekable_writer::<S, _>(
+                config,
+                image,
+                mode,
+                OutputFormat::Wav,
+                &mut v,
+            )?;
             Ok(v)
         }
-        Err(e) => {
-            return Err(FileError::CreateFileError(e.to_string()));
-        }
+        Err(e) => Err(EncodeError::CreateFileError(e.to_string())),
     }
 }
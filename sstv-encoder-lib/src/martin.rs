@@ -1,7 +1,11 @@
 // Copyright 2025 BenderBlog Rodriguez and Contributors.
 // SPDX-License-Identifier: 0BSD
 
-use crate::{Mode, SampleGenerator, sstv_image::SSTVImage};
+use crate::{
+    ModeSpec,
+    sstv_image::SSTVImage,
+    tone::{ToneLabel, ToneSequence},
+};
 
 ///
 /// Martin mode
@@ -12,66 +16,66 @@ use crate::{Mode, SampleGenerator, sstv_image::SSTVImage};
 /// |------------|-------|
 /// | Martin 1  |   44  |
 /// | Martin 2  |   40  |
+/// | Martin 3  |   36  |
+/// | Martin 4  |   32  |
 ///
 /// Color Mode: RGB (1500-2300hz luminance range)
 /// Scan Sequence: Green, Blue, Red
 ///
-/// Image Size: 320x256 (including 16-line header)
+/// Image Size: 320x256 (including 16-line header), 320x128 for Martin 3 and 4
 ///
-pub(crate) fn encode_in_martin(
-    ctx: &mut SampleGenerator,
+/// Martin 3 and 4 are Martin 1 and 2 with half of the lines.
+///
+pub(crate) fn encode_line_in_martin(
+    tones: &mut ToneSequence,
     image: &SSTVImage,
-    mode: &Mode,
-) -> Vec<i16> {
-    let mut result = vec![];
-
-    let pixel_scan_time = match mode {
-        Mode::Martin1 => 0.4576,
-        Mode::Martin2 => 0.2288,
-        _ => 0.0, // TODO: Throw illegal call exception.
-    };
-
-    let image_to_send = image.resize_image(320, 256);
-
-    for y in 0..image_to_send.get_height() {
-        // Step 1: The Sync Pulse
-        result.extend(ctx.generate_samples(4.862, 1200.0));
+    spec: &ModeSpec,
+    line: usize,
+) {
+    let pixel_scan_time = spec.pixel_time;
+    let [first, second, third] = [0, 1, 2].map(|i| spec.scan_order[i]);
 
-        // Step 2: The Sync Porch
-        result.extend(ctx.generate_samples(0.572, 1500.0));
+    let y = line;
 
-        // Step 3: The green scan
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(
-                ctx.generate_color_samples(pixel_scan_time, image_to_send.get_rgb_pixel(x, y)[1]),
-            )
-        });
+    // Step 1: The Sync Pulse
+    tones.push(1200.0, spec.sync_time, ToneLabel::Sync);
 
-        // Step 4: The separator pulse
-        result.extend(ctx.generate_samples(0.572, 1500.0));
+    // Step 2: The Sync Porch
+    tones.push(1500.0, spec.porch_time, ToneLabel::Porch);
 
-        // Step 5: The blue scan
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(
-                ctx.generate_color_samples(pixel_scan_time, image_to_send.get_rgb_pixel(x, y)[2]),
-            )
-        });
+    // Step 3: The green scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[first.index()],
+            pixel_scan_time,
+            first,
+        )
+    });
 
-        // Step 6: The separator pulse
-        result.extend(ctx.generate_samples(0.572, 1500.0));
+    // Step 4: The separator pulse
+    tones.push(1500.0, spec.separator_time, ToneLabel::Separator);
 
-        // Step 7: The red scan
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(
-                ctx.generate_color_samples(pixel_scan_time, image_to_send.get_rgb_pixel(x, y)[0]),
-            )
-        });
+    // Step 5: The blue scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[second.index()],
+            pixel_scan_time,
+            second,
+        )
+    });
 
-        // Step 8: The separator pulse
-        result.extend(ctx.generate_samples(0.572, 1500.0));
+    // Step 6: The separator pulse
+    tones.push(1500.0, spec.separator_time, ToneLabel::Separator);
 
-        // Repeat the above sequence for 256 lines.
-    }
+    // Step 7: The red scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[third.index()],
+            pixel_scan_time,
+            third,
+        )
+    });
 
-    result
+    // Step 8: The separator pulse
+    tones.push(1500.0, spec.separator_time, ToneLabel::Separator);
 }
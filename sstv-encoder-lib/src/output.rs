@@ -0,0 +1,202 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use std::io::{self, Write};
+
+use crate::{EncodeError, SSTVEncoder, Sample};
+
+/// The format tag of integer pcm data in a WAV file.
+const WAVE_FORMAT_PCM: u16 = 1;
+
+/// The format tag of IEEE float data in a WAV file.
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+
+/// The format tag of a WAV file whose format is the sub format GUID of the fmt chunk,
+/// needed by integer pcm data of more than 16 bits.
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+/// KSDATAFORMAT_SUBTYPE_PCM, the sub format GUID of integer pcm data.
+const SUBTYPE_PCM: [u8; 16] = [
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+];
+
+/// The channel mask of a mono WAVE_FORMAT_EXTENSIBLE file.
+const SPEAKER_FRONT_CENTER: u32 = 0x4;
+
+/// Length of the Sun .au header, which is also the offset of the data.
+const AU_HEADER_SIZE: u32 = 24;
+
+/// The data size of a Sun .au file whose length is unknown.
+const AU_UNKNOWN_SIZE: u32 = 0xffffffff;
+
+/// Samples generated and written at once.
+const WRITE_CHUNK_SIZE: usize = 4096;
+
+///
+/// The container of the pcm samples, the sample format comes from the [`Sample`] type.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// RIFF WAVE, little endian, with u8, i16, I24, i32 or f32 samples.
+    #[default]
+    Wav,
+    /// Headerless little endian pcm samples.
+    Raw,
+    /// Sun .au, big endian, u8 samples are stored as signed.
+    Au,
+}
+
+impl OutputFormat {
+    /// Bytes of the header, with the length of the data if known.
+    pub(crate) fn header<S: Sample>(
+        &self,
+        sample_rate: u32,
+        sample_count: Option<usize>,
+    ) -> Vec<u8> {
+        match self {
+            OutputFormat::Wav => wav_header::<S>(sample_rate, sample_count.unwrap_or(0)),
+            OutputFormat::Raw => vec![],
+            OutputFormat::Au => au_header::<S>(
+                sample_rate,
+                sample_count.map_or(AU_UNKNOWN_SIZE, data_size::<S>),
+            ),
+        }
+    }
+
+    /// Append the bytes of the samples.
+    pub(crate) fn extend_samples<S: Sample>(&self, samples: &[S], bytes: &mut Vec<u8>) {
+        match self {
+            OutputFormat::Wav | OutputFormat::Raw => samples
+                .iter()
+                .for_each(|sample| sample.extend_le_bytes(bytes)),
+            OutputFormat::Au => samples
+                .iter()
+                .for_each(|sample| sample.extend_be_bytes(bytes)),
+        }
+    }
+
+    /// Bytes after the samples, WAV chunks are aligned to the even bytes.
+    pub(crate) fn trailer<S: Sample>(&self, sample_count: usize) -> Vec<u8> {
+        match self {
+            OutputFormat::Wav if data_size::<S>(sample_count) % 2 == 1 => vec![0],
+            _ => vec![],
+        }
+    }
+}
+
+/// Bytes of the samples, as much as the 32 bit length fields could hold.
+fn data_size<S: Sample>(sample_count: usize) -> u32 {
+    (sample_count * (S::BITS_PER_SAMPLE / 8) as usize).min(u32::MAX as usize) as u32
+}
+
+///
+/// Mono WAV header.
+///
+/// Float data has a fmt chunk with the extension size and a fact chunk with the sample
+/// count, and integer data of more than 16 bits is WAVE_FORMAT_EXTENSIBLE, as the WAV
+/// format asks for. Its length never depends on the sample count.
+///
+fn wav_header<S: Sample>(sample_rate: u32, sample_count: usize) -> Vec<u8> {
+    let block_align = S::BITS_PER_SAMPLE / 8;
+    let data_size = data_size::<S>(sample_count);
+    let extensible = !S::IS_FLOAT && S::BITS_PER_SAMPLE > 16;
+    let format = if S::IS_FLOAT {
+        WAVE_FORMAT_IEEE_FLOAT
+    } else if extensible {
+        WAVE_FORMAT_EXTENSIBLE
+    } else {
+        WAVE_FORMAT_PCM
+    };
+
+    let mut fmt = Vec::with_capacity(40);
+    fmt.extend_from_slice(&format.to_le_bytes());
+    // Mono
+    fmt.extend_from_slice(&1u16.to_le_bytes());
+    fmt.extend_from_slice(&sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&S::BITS_PER_SAMPLE.to_le_bytes());
+    if S::IS_FLOAT {
+        // No extension.
+        fmt.extend_from_slice(&0u16.to_le_bytes());
+    } else if extensible {
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        // Valid bits.
+        fmt.extend_from_slice(&S::BITS_PER_SAMPLE.to_le_bytes());
+        fmt.extend_from_slice(&SPEAKER_FRONT_CENTER.to_le_bytes());
+        fmt.extend_from_slice(&SUBTYPE_PCM);
+    }
+
+    let mut chunks = Vec::with_capacity(fmt.len() + 32);
+    chunks.extend_from_slice(b"fmt ");
+    chunks.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+    chunks.extend_from_slice(&fmt);
+    if S::IS_FLOAT {
+        let frame_count = sample_count.min(u32::MAX as usize) as u32;
+        chunks.extend_from_slice(b"fact");
+        chunks.extend_from_slice(&4u32.to_le_bytes());
+        chunks.extend_from_slice(&frame_count.to_le_bytes());
+    }
+    chunks.extend_from_slice(b"data");
+    chunks.extend_from_slice(&data_size.to_le_bytes());
+
+    // Everything after the RIFF size, the data and its padding.
+    let riff_size = data_size.saturating_add(4 + chunks.len() as u32 + data_size % 2);
+    let mut bytes = Vec::with_capacity(12 + chunks.len());
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&riff_size.to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(&chunks);
+    bytes
+}
+
+/// Mono Sun .au header.
+fn au_header<S: Sample>(sample_rate: u32, data_size: u32) -> Vec<u8> {
+    let encoding: u32 = match (S::IS_FLOAT, S::BITS_PER_SAMPLE) {
+        (true, _) => 6,
+        (false, 8) => 2,
+        (false, 16) => 3,
+        (false, 24) => 4,
+        (false, _) => 5,
+    };
+
+    let mut bytes = Vec::with_capacity(AU_HEADER_SIZE as usize);
+    bytes.extend_from_slice(b".snd");
+    bytes.extend_from_slice(&AU_HEADER_SIZE.to_be_bytes());
+    bytes.extend_from_slice(&data_size.to_be_bytes());
+    bytes.extend_from_slice(&encoding.to_be_bytes());
+    bytes.extend_from_slice(&sample_rate.to_be_bytes());
+    // Mono
+    bytes.extend_from_slice(&1u32.to_be_bytes());
+    bytes
+}
+
+/// Write the bytes, mapping the io error.
+pub(crate) fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> Result<(), EncodeError> {
+    writer.write_all(bytes).map_err(write_error)
+}
+
+pub(crate) fn write_error(error: io::Error) -> EncodeError {
+    EncodeError::WriteFileError(error.to_string())
+}
+
+/// Generate the samples chunk by chunk and write them, return how many are written.
+pub(crate) fn write_samples<S: Sample>(
+    encoder: &mut SSTVEncoder<S>,
+    format: OutputFormat,
+    writer: &mut impl Write,
+) -> Result<usize, EncodeError> {
+    let mut samples = vec![S::from_f32(0.0); WRITE_CHUNK_SIZE];
+    let mut bytes = Vec::with_capacity(WRITE_CHUNK_SIZE * 4);
+    let mut count = 0;
+    loop {
+        let written = encoder.fill(&mut samples);
+        if written == 0 {
+            return Ok(count);
+        }
+        bytes.clear();
+        format.extend_samples(&samples[..written], &mut bytes);
+        write_bytes(writer, &bytes)?;
+        count += written;
+    }
+}
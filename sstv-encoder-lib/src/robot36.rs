@@ -1,7 +1,11 @@
 // Copyright 2025 BenderBlog Rodriguez and Contributors.
 // SPDX-License-Identifier: 0BSD
 
-use crate::{SampleGenerator, sstv_image::SSTVImage};
+use crate::{
+    Channel, ModeSpec,
+    sstv_image::SSTVImage,
+    tone::{ToneLabel, ToneSequence},
+};
 
 ///
 /// Robot 36 Mode
@@ -23,74 +27,56 @@ use crate::{SampleGenerator, sstv_image::SSTVImage};
 ///  • The R-Y and B-Y scans have only 1/2 the period (44ms) of the Y scan. (88ms)
 ///  • Even lines use a 1500hz “separator” pulse, while odd lines use 2300hz.
 ///
-pub(crate) fn encode_in_robot36(ctx: &mut SampleGenerator, image: &SSTVImage) -> Vec<i16> {
-    let mut result = vec![];
-
-    let image_to_send = image.resize_image(320, 240);
-
-    for y in (0..image_to_send.get_height()).step_by(2) {
-        // Step 1: Sync Pulse
-        result.extend(ctx.generate_samples(9.0, 1200.0));
-
-        // Step 2: Sync Porch
-        result.extend(ctx.generate_samples(3.0, 1500.0));
-
-        // Step 3: Odd line Y scan, total time 88ms
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(ctx.generate_color_samples(
-                88.0 / image_to_send.get_width() as f32,
-                image_to_send.get_ycrcb_pixel(x, y)[0],
-            ));
-        });
-
-        // Step 4: "Even" separator pulse
-        result.extend(ctx.generate_samples(4.5, 1500.0));
-
-        // Step 5: Porch
-        result.extend(ctx.generate_samples(1.5, 1900.0));
-
-        // Step 6: R-Y scan, total time 44ms
-        (0..image_to_send.get_width()).for_each(|x| {
-            let odd_pixel = image_to_send.get_ycrcb_pixel(x, y)[1];
-            let even_pixel = image_to_send.get_ycrcb_pixel(x, y + 1)[1];
-            let avg_freq = ((odd_pixel as u16 + even_pixel as u16) >> 1) as u8;
-            result.extend(
-                ctx.generate_color_samples(44.0 / image_to_send.get_width() as f32, avg_freq),
-            );
-        });
-
-        // Step 7: Sync Pulse
-        result.extend(ctx.generate_samples(9.0, 1200.0));
-
-        // Step 8: Sync Porch
-        result.extend(ctx.generate_samples(3.0, 1500.0));
-
-        // Step 9: Even Y scan, total time 88ms
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(ctx.generate_color_samples(
-                88.0 / image_to_send.get_width() as f32,
-                image_to_send.get_ycrcb_pixel(x, y + 1)[0],
-            ));
-        });
-
-        // Step 10: Separator Pulse
-        result.extend(ctx.generate_samples(4.5, 2300.0));
-
-        // Step 11: Porch
-        result.extend(ctx.generate_samples(1.5, 1500.0));
-
-        // Step 12: B-Y scan, total time 69ms
-        (0..image_to_send.get_width()).for_each(|x| {
-            let odd_pixel = image_to_send.get_ycrcb_pixel(x, y)[2];
-            let even_pixel = image_to_send.get_ycrcb_pixel(x, y + 1)[2];
-            let avg_freq = ((odd_pixel as u16 + even_pixel as u16) >> 1) as u8;
-            result.extend(
-                ctx.generate_color_samples(44.0 / image_to_send.get_width() as f32, avg_freq),
-            );
-        });
-
-        // Repeat the above sequence for 240 lines.
-    }
-
-    result
+pub(crate) fn encode_line_in_robot36(
+    tones: &mut ToneSequence,
+    image: &SSTVImage,
+    spec: &ModeSpec,
+    line: usize,
+) {
+    let y_pixel_time = spec.scan_time(Channel::Y) / image.get_width() as f64;
+    let chroma_pixel_time = spec.scan_time(Channel::RY) / image.get_width() as f64;
+
+    let y = line;
+    // The first line of the pair, which shares the chroma.
+    let pair = y - y % 2;
+    // Even lines send R-Y with a 1500hz separator, odd lines send B-Y with 2300hz.
+    let (chroma, separator_frequency, porch_frequency) = if y.is_multiple_of(2) {
+        (Channel::RY, 1500.0, 1900.0)
+    } else {
+        (Channel::BY, 2300.0, 1500.0)
+    };
+
+    // Step 1: Sync Pulse
+    tones.push(1200.0, spec.sync_time, ToneLabel::Sync);
+
+    // Step 2: Sync Porch
+    tones.push(1500.0, spec.porch_time, ToneLabel::Porch);
+
+    // Step 3: Y scan, total time 88ms
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(image.get_ycrcb_pixel(x, y)[0], y_pixel_time, Channel::Y);
+    });
+
+    // Step 4: "Even" or "Odd" separator pulse
+    tones.push(
+        separator_frequency,
+        spec.separator_time,
+        ToneLabel::Separator,
+    );
+
+    // Step 5: Porch
+    tones.push(
+        porch_frequency,
+        spec.separator_porch_time,
+        ToneLabel::SeparatorPorch,
+    );
+
+    // Step 6: R-Y or B-Y scan averaged for two lines, total time 44ms
+    (0..image.get_width()).for_each(|x| {
+        let odd_pixel = image.get_ycrcb_pixel(x, pair)[chroma.index()];
+        let even_pixel =
+            image.get_ycrcb_pixel(x, (pair + 1).min(image.get_height() - 1))[chroma.index()];
+        let avg_freq = ((odd_pixel as u16 + even_pixel as u16) >> 1) as u8;
+        tones.push_color(avg_freq, chroma_pixel_time, chroma);
+    });
 }
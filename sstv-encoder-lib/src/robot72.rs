@@ -1,7 +1,11 @@
 // Copyright 2025 BenderBlog Rodriguez and Contributors.
 // SPDX-License-Identifier: 0BSD
 
-use crate::{SampleGenerator, sstv_image::SSTVImage};
+use crate::{
+    Channel, ModeSpec,
+    sstv_image::SSTVImage,
+    tone::{ToneLabel, ToneSequence},
+};
 
 ///
 /// Robot 72 Mode
@@ -10,63 +14,65 @@ use crate::{SampleGenerator, sstv_image::SSTVImage};
 ///
 /// |Mode|Code in Decimal|
 /// |----------|----|
+/// | Robot 24 |  4 |
 /// | Robot 72 | 12 |
 ///
 /// Color Mode: YRyBy (1500-2300hz luminance range)
 /// Scan Sequence: Y, Ry, By
 ///
-/// Image Size: 320x240 (including 16-line header)
+/// Image Size: 320x240 (including 16-line header), 160x120 for Robot 24
 ///
-pub(crate) fn encode_in_robot72(ctx: &mut SampleGenerator, image: &SSTVImage) -> Vec<i16> {
-    let mut result = vec![];
-
-    let image_to_send = image.resize_image(320, 240);
-
-    for y in 0..image_to_send.get_height() {
-        // Step 1: Sync Pulse
-        result.extend(ctx.generate_samples(9.0, 1200.0));
+/// Robot 24 is sent as Robot 72 at half the size, with the scan times of Robot 36.
+///
+pub(crate) fn encode_line_in_robot72(
+    tones: &mut ToneSequence,
+    image: &SSTVImage,
+    spec: &ModeSpec,
+    line: usize,
+) {
+    let y_pixel_time = spec.scan_time(Channel::Y) / image.get_width() as f64;
+    let chroma_pixel_time = spec.scan_time(Channel::RY) / image.get_width() as f64;
 
-        // Step 2: Sync Porch
-        result.extend(ctx.generate_samples(3.0, 1500.0));
+    let y = line;
 
-        // Step 3: Y scan, total time 138ms
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(ctx.generate_color_samples(
-                138.0 / image_to_send.get_width() as f32,
-                image_to_send.get_ycrcb_pixel(x, y)[0],
-            ));
-        });
+    // Step 1: Sync Pulse
+    tones.push(1200.0, spec.sync_time, ToneLabel::Sync);
 
-        // Step 6: Separator Pulse
-        result.extend(ctx.generate_samples(4.5, 1500.0));
+    // Step 2: Sync Porch
+    tones.push(1500.0, spec.porch_time, ToneLabel::Porch);
 
-        // Step 7: Porch
-        result.extend(ctx.generate_samples(1.5, 1900.0));
+    // Step 3: Y scan, total time 138ms (88ms in Robot 24)
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(image.get_ycrcb_pixel(x, y)[0], y_pixel_time, Channel::Y);
+    });
 
-        // Step 8: R-Y scan, total time 69ms
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(ctx.generate_color_samples(
-                69.0 / image_to_send.get_width() as f32,
-                image_to_send.get_ycrcb_pixel(x, y)[1],
-            ));
-        });
+    // Step 6: Separator Pulse
+    tones.push(1500.0, spec.separator_time, ToneLabel::Separator);
 
-        // Step 9: Separator Pulse
-        result.extend(ctx.generate_samples(4.5, 2300.0));
+    // Step 7: Porch
+    tones.push(1900.0, spec.separator_porch_time, ToneLabel::SeparatorPorch);
 
-        // Step 10: Porch
-        result.extend(ctx.generate_samples(1.5, 1500.0));
+    // Step 8: R-Y scan, total time 69ms (44ms in Robot 24)
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_ycrcb_pixel(x, y)[1],
+            chroma_pixel_time,
+            Channel::RY,
+        );
+    });
 
-        // Step 11: B-Y scan, total time 69ms
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(ctx.generate_color_samples(
-                69.0 / image_to_send.get_width() as f32,
-                image_to_send.get_ycrcb_pixel(x, y)[2],
-            ));
-        });
+    // Step 9: Separator Pulse
+    tones.push(2300.0, spec.separator_time, ToneLabel::Separator);
 
-        // Repeat the above sequence for 240 lines.
-    }
+    // Step 10: Porch
+    tones.push(1500.0, spec.separator_porch_time, ToneLabel::SeparatorPorch);
 
-    result
+    // Step 11: B-Y scan, total time 69ms (44ms in Robot 24)
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_ycrcb_pixel(x, y)[2],
+            chroma_pixel_time,
+            Channel::BY,
+        );
+    });
 }
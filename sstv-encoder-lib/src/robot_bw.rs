@@ -0,0 +1,52 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use crate::{
+    Channel, ModeSpec,
+    sstv_image::SSTVImage,
+    tone::{ToneLabel, ToneSequence},
+};
+
+///
+/// Robot Black and White Modes
+///
+/// VIS Code:
+///
+/// |Mode|Code in Decimal|
+/// |--------------|----|
+/// | Robot 8 B/W  |  2 |
+/// | Robot 12 B/W |  6 |
+/// | Robot 24 B/W | 10 |
+/// | Robot 36 B/W | 14 |
+///
+/// Color Mode: Luminance only (1500-2300hz brightness range)
+/// Scan Sequence: Y
+///
+/// Image Size: 160x120 for Robot 8 and 12, 320x240 for Robot 24 and 36 (including
+/// 16-line header)
+///
+/// Reminders:
+///  • There is no separator, a line is the sync pulse, the porch and the scan.
+///  • The brightness uses the whole 1500-2300hz range, unlike the Y of the color modes.
+///
+pub(crate) fn encode_line_in_robot_bw(
+    tones: &mut ToneSequence,
+    image: &SSTVImage,
+    spec: &ModeSpec,
+    line: usize,
+) {
+    let pixel_time = spec.scan_time(Channel::Y) / image.get_width() as f64;
+
+    let y = line;
+
+    // Step 1: Sync Pulse
+    tones.push(1200.0, spec.sync_time, ToneLabel::Sync);
+
+    // Step 2: Sync Porch
+    tones.push(1500.0, spec.porch_time, ToneLabel::Porch);
+
+    // Step 3: Y scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(image.get_luminance(x, y), pixel_time, Channel::Y);
+    });
+}
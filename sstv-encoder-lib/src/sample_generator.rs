@@ -1,120 +1,221 @@
 // Copyright 2025 BenderBlog Rodriguez and Contributors.
 // SPDX-License-Identifier: 0BSD
 
+use std::f64::consts::TAU;
+
+use sstv_core::{Sample, nearest_sample};
+
+use crate::{
+    EncoderConfig,
+    shaping_filter::ShapingFilter,
+    tone::{ToneLabel, ToneSequence},
+};
+
+/// The phase accumulator wraps around once per cycle at 2^32.
+const PHASE_SCALE: f64 = 4294967296.0;
+
+/// The top bits of the phase index the sine table.
+const SINE_TABLE_BITS: u32 = 10;
+
+/// The lower bits of the phase interpolate between two entries of the sine table.
+const SINE_FRACTION_BITS: u32 = 32 - SINE_TABLE_BITS;
+
+/// The phases collected before they are turned into samples, so a long sequence of tones
+/// does not hold the phase of every sample.
+const MAX_PHASES: usize = 1 << 16;
+
 ///
 /// SampleGenerator is a generator to generate constant stream of pcm wave data.
 ///
-pub struct SampleGenerator {
+/// It is a numerically controlled oscillator: the phase is a 32 bit accumulator which
+/// advances by the frequency every sample, so the phase is exactly continuous when the
+/// frequency changes.
+///
+pub(crate) struct SampleGenerator {
     /// The sample rate of the result wave file
     sample_rate: u32,
 
-    /// The older amplitude, for continuous phase
-    older_data: f32,
+    /// The peak amplitude of the samples, 1 is the full scale
+    amplitude: f32,
+
+    /// The phase of the next sample, a full cycle is 2^32
+    phase: u32,
 
-    /// The older cosine phase, for continuous phase
-    older_cos: f32,
+    /// One cycle of sine, with the first entry repeated at the end for interpolation
+    sine_table: Option<Vec<f32>>,
 
-    /// The delta of the length, for compensating the precision related to the sample rate
-    delta_length: f32,
+    /// The low pass filter of the frequency, for the shaped modulation
+    shaping_filter: Option<ShapingFilter>,
+
+    /// The ideal time since the start of the transmission, in ms
+    elapsed_time: f64,
+
+    /// The samples generated since the start of the transmission
+    elapsed_samples: u64,
+
+    /// The phase of every sample of the tones not yet turned into samples
+    phases: Vec<u32>,
 }
 
 impl SampleGenerator {
-    fn sign(&self, num: f32) -> f32 {
-        if num >= 0.0 {
-            return 1.0;
-        } else if num < 0.0 {
-            return -1.0;
-        } else {
-            return 0.0;
+    /// Create a new sample generator with the sample rate, the amplitude and the sine table
+    /// of the config.
+    pub(crate) fn from_config(config: &EncoderConfig) -> Self {
+        Self {
+            sample_rate: config.sample_rate(),
+            amplitude: config.amplitude(),
+            phase: 0,
+            sine_table: config.sine_table().then(|| {
+                (0..=1 << SINE_TABLE_BITS)
+                    .map(|index| (TAU * index as f64 / (1 << SINE_TABLE_BITS) as f64).sin() as f32)
+                    .collect()
+            }),
+            shaping_filter: None,
+            elapsed_time: 0.0,
+            elapsed_samples: 0,
+            phases: vec![],
         }
     }
 
-    /// Create a new sample generator.
-    pub fn new(sample_rate: u32) -> Self {
-        Self {
-            sample_rate: sample_rate,
-            older_data: 0.0,
-            older_cos: 0.0,
-            delta_length: 0.0,
-        }
+    /// Let the frequency glide over the time, in ms, instead of stepping between tones.
+    pub(crate) fn shape_frequency(&mut self, glide_time: f64) {
+        let length = (glide_time * self.sample_rate as f64 / 1000.0).round() as usize;
+        self.shaping_filter = Some(ShapingFilter::new(length));
     }
 
     ///
-    /// Generate pcm wave data from color signal strength.
+    /// Generate pcm wave data of the tones in the sample format `S`, after the samples
+    /// already in the buffer.
     ///
-    /// Note that the library uses 8 bit unsigned integer value to store the color signal
-    /// strength, and the range of the color frequency is [1500,2300].
+    /// The tones only collect the phase of their samples, and the sine of the phases is
+    /// taken at once before a silence and at the end. The tones are often a few samples
+    /// long, so this keeps the loop over the samples long.
     ///
-    /// Refrence is at below:
+    pub(crate) fn render<S: Sample>(&mut self, tones: &ToneSequence, samples: &mut Vec<S>) {
+        for tone in tones.tones() {
+            match tone.label {
+                ToneLabel::Silence => {
+                    self.flush_phases(samples);
+                    self.extend_silence(samples, tone.duration);
+                }
+                _ => {
+                    self.extend_tone(tone.duration, tone.frequency);
+                    if self.phases.len() >= MAX_PHASES {
+                        self.flush_phases(samples);
+                    }
+                }
+            }
+        }
+        self.flush_phases(samples);
+    }
+
+    ///
+    /// Count the samples of the duration.
     ///
-    /// > Dayton Paper Appendix A: RGB Color Encoding
-    /// >
-    /// > SSTV systems use the frequency range of 1500-2300hz to represent
-    /// > the range of brightness values from pure black to pure white.
-    /// > Because of this, we must convert out 8-bit R,G,B values into the
-    /// > appropriate frequency.
-    /// >
-    /// > In this formula, the RGB value is stored in 8 bit non-signed integer.
-    /// >
-    /// > $$ f = 1500 + s_r * 3.1372549 $$
-    /// >
-    /// > Where $f$ is the frequency of the strength of the value $s_r$.
+    /// The end of every tone is the [`nearest_sample`] to its ideal time since the start
+    /// of the transmission, so rounding never adds up over the tones, and a line always
+    /// starts within one sample of its ideal time.
+    ///
+    fn sample_count(&mut self, duration_in_ms: f64) -> usize {
+        self.elapsed_time += duration_in_ms;
+        let end = nearest_sample(self.elapsed_time, self.sample_rate);
+        let num_samples = end.saturating_sub(self.elapsed_samples);
+        self.elapsed_samples += num_samples;
+        num_samples as usize
+    }
+
     ///
-    /// > Dayton Paper Appendix B: YRyBy (YCrCb) Color Encoding
-    /// >
-    /// > Again, as with RGB encoding, these values can be converted to frequency, for
-    /// > SSTV transmission:
-    /// >
-    /// > $$ f = 1500 + v * 3.1372549 $$
-    /// >
-    /// > Where 'v' is the value of the Y, R-Y, or B-Y
+    /// Generate silence, the next tone starts from the zero phase.
     ///
-    pub(crate) fn generate_color_samples(&mut self, duration_in_ms: f32, strength: u8) -> Vec<i16> {
-        let frequency = 1500.0 + (strength as f32) * 800.0 / 255.0;
-        self.generate_samples(duration_in_ms, frequency)
+    fn extend_silence<S: Sample>(&mut self, samples: &mut Vec<S>, duration_in_ms: f64) {
+        let num_samples = self.sample_count(duration_in_ms);
+        self.phase = 0;
+        if let Some(filter) = &mut self.shaping_filter {
+            filter.reset();
+        }
+        samples.resize(samples.len() + num_samples, S::from_f32(0.0));
     }
 
     ///
-    /// Generate pcm wave data.
+    /// Collect the phases of a tone.
     ///
-    pub(crate) fn generate_samples(&mut self, duration_in_ms: f32, frequency: f32) -> Vec<i16> {
-        // Count the amount of the sample rates
-        let mut num_samples: i32 =
-            (self.sample_rate as f32 * duration_in_ms / 1000.0).floor() as i32;
-
-        // Compensating the precision if needed
-        self.delta_length +=
-            self.sample_rate as f32 * duration_in_ms / 1000.0 - (num_samples as f32);
-        if self.delta_length >= 1.0 {
-            num_samples += self.delta_length.floor() as i32;
-            self.delta_length -= self.delta_length.floor();
+    /// With the shaped modulation, the frequency of every sample goes through the shaping
+    /// filter first.
+    ///
+    fn extend_tone(&mut self, duration_in_ms: f64, frequency: f32) {
+        let num_samples = self.sample_count(duration_in_ms);
+        let sample_rate = self.sample_rate as f64;
+        let phase_increment =
+            |frequency: f32| (frequency as f64 / sample_rate * PHASE_SCALE).round() as u32;
+
+        match &mut self.shaping_filter {
+            Some(filter) => {
+                let mut phase = self.phase;
+                self.phases.extend((0..num_samples).map(|_| {
+                    let current = phase;
+                    phase = phase.wrapping_add(phase_increment(filter.run(frequency)));
+                    current
+                }));
+                self.phase = phase;
+            }
+            None => {
+                let increment = phase_increment(frequency);
+                let phase = self.phase;
+                self.phases.extend(
+                    (0..num_samples as u32)
+                        .map(|index| phase.wrapping_add(increment.wrapping_mul(index))),
+                );
+                self.phase = phase.wrapping_add(increment.wrapping_mul(num_samples as u32));
+            }
         }
+    }
+
+    /// Turn the collected phases into samples.
+    fn flush_phases<S: Sample>(&mut self, samples: &mut Vec<S>) {
+        let amplitude = self.amplitude;
+        let start = samples.len();
+        samples.resize(start + self.phases.len(), S::from_f32(0.0));
+        let pairs = samples[start..].iter_mut().zip(&self.phases);
+        match &self.sine_table {
+            Some(table) => {
+                for (sample, &phase) in pairs {
+                    *sample = S::from_f32(amplitude * table_sine(table, phase));
+                }
+            }
+            None => {
+                for (sample, &phase) in pairs {
+                    *sample = S::from_f32(amplitude * sine(phase));
+                }
+            }
+        }
+        self.phases.clear();
+    }
+}
 
-        // Generate phi sample
-        let phi_samples = self.sample_rate as f32
-            * (self.sign(self.older_cos) * self.older_data.asin()
-                + (self.sign(self.older_cos) - 1.0).abs() / 2.0 * core::f32::consts::PI);
-
-        // Write samples
-        (0..num_samples)
-            .map(move |tick| {
-                // Remember older_data and older_sum
-                self.older_data =
-                    ((2.0 * core::f32::consts::PI * frequency * (num_samples as f32)
-                        + phi_samples)
-                        / self.sample_rate as f32)
-                        .sin();
-                self.older_cos = ((2.0 * core::f32::consts::PI * frequency * (num_samples as f32)
-                    + phi_samples)
-                    / self.sample_rate as f32)
-                    .cos();
-
-                (32767.0
-                    * ((2.0 * core::f32::consts::PI * frequency * (tick as f32) + phi_samples)
-                        / self.sample_rate as f32)
-                        .sin())
-                .floor() as i16
-            })
-            .collect()
+/// Sine of the phase, from the Taylor series around zero.
+///
+/// The phase is folded into [-pi/2, pi/2] first, where the terms up to x^11 are exact
+/// to the precision of f32.
+fn sine(phase: u32) -> f32 {
+    let mut phase = phase as i32;
+    if phase.unsigned_abs() > 1 << 30 {
+        // sin(x) = sin(pi - x), and the same around -pi.
+        phase = i32::MIN.wrapping_sub(phase);
     }
+    let x = phase as f32 * (core::f32::consts::PI / 2147483648.0);
+    let x2 = x * x;
+    let series = 1.0 / 39916800.0;
+    let series = 1.0 / 362880.0 - x2 * series;
+    let series = 1.0 / 5040.0 - x2 * series;
+    let series = 1.0 / 120.0 - x2 * series;
+    let series = 1.0 / 6.0 - x2 * series;
+    x * (1.0 - x2 * series)
+}
+
+/// Sine of the phase, interpolated from the table.
+fn table_sine(table: &[f32], phase: u32) -> f32 {
+    let index = (phase >> SINE_FRACTION_BITS) as usize;
+    let fraction =
+        (phase & ((1 << SINE_FRACTION_BITS) - 1)) as f32 / (1 << SINE_FRACTION_BITS) as f32;
+    table[index] + (table[index + 1] - table[index]) * fraction
 }
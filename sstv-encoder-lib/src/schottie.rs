@@ -1,7 +1,11 @@
 // Copyright 2025 BenderBlog Rodriguez and Contributors.
 // SPDX-License-Identifier: 0BSD
 
-use crate::{Mode, SampleGenerator, sstv_image::SSTVImage};
+use crate::{
+    ModeSpec,
+    sstv_image::SSTVImage,
+    tone::{ToneLabel, ToneSequence},
+};
 
 ///
 /// Scottie mode
@@ -12,66 +16,68 @@ use crate::{Mode, SampleGenerator, sstv_image::SSTVImage};
 /// |------------|-------|
 /// | Scottie 1  |   60  |
 /// | Scottie 2  |   56  |
+/// | Scottie 3  |   52  |
+/// | Scottie 4  |   48  |
 /// | Scottie DX |   76  |
 ///
 /// Color Mode: RGB (1500-2300hz luminance range)
 /// Scan Sequence: Green, Blue, Red
 ///
-/// Image Size: 320x256 (including 16-line header)
+/// Image Size: 320x256 (including 16-line header), 320x128 for Scottie 3 and 4
 ///
-pub(crate) fn encode_in_schottie(
-    ctx: &mut SampleGenerator,
+/// Scottie 3 and 4 are Scottie 1 and 2 with half of the lines.
+///
+pub(crate) fn encode_line_in_schottie(
+    tones: &mut ToneSequence,
     image: &SSTVImage,
-    mode: &Mode,
-) -> Vec<i16> {
-    let mut result = vec![];
-    let pixel_scan_time = match mode {
-        Mode::Scottie1 => 0.4320,
-        Mode::Scottie2 => 0.2752,
-        Mode::ScottieDx => 1.08,
-        _ => 0.0, // TODO: Throw illegal call exception.
-    };
+    spec: &ModeSpec,
+    line: usize,
+) {
+    let pixel_scan_time = spec.pixel_time;
+    let [first, second, third] = [0, 1, 2].map(|i| spec.scan_order[i]);
 
-    let image_to_send = image.resize_image(320, 256);
+    let y = line;
 
     // Step 1: Add out-sync "Starting" sync pulse (first line only!)
-    result.extend(ctx.generate_samples(9.0, 1200.0));
-
-    for y in 0..image_to_send.get_height() {
-        // Step 2: The separator pulse
-        result.extend(ctx.generate_samples(1.5, 1500.0));
-
-        // Step 3: The green scan
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(
-                ctx.generate_color_samples(pixel_scan_time, image_to_send.get_rgb_pixel(x, y)[1]),
-            )
-        });
+    if y == 0 {
+        tones.push(1200.0, spec.sync_time, ToneLabel::Sync);
+    }
 
-        // Step 4: The separator pulse
-        result.extend(ctx.generate_samples(1.5, 1500.0));
+    // Step 2: The separator pulse
+    tones.push(1500.0, spec.separator_time, ToneLabel::Separator);
 
-        // Step 5: The blue scan
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(
-                ctx.generate_color_samples(pixel_scan_time, image_to_send.get_rgb_pixel(x, y)[2]),
-            )
-        });
-        // Step 6: The sync pulse
-        result.extend(ctx.generate_samples(9.0, 1200.0));
+    // Step 3: The green scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[first.index()],
+            pixel_scan_time,
+            first,
+        )
+    });
 
-        // Step 7: The sync porch
-        result.extend(ctx.generate_samples(1.5, 1500.0));
+    // Step 4: The separator pulse
+    tones.push(1500.0, spec.separator_time, ToneLabel::Separator);
 
-        // Step 8: The red scan
-        (0..image_to_send.get_width()).for_each(|x| {
-            result.extend(
-                ctx.generate_color_samples(pixel_scan_time, image_to_send.get_rgb_pixel(x, y)[0]),
-            )
-        });
+    // Step 5: The blue scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[second.index()],
+            pixel_scan_time,
+            second,
+        )
+    });
+    // Step 6: The sync pulse
+    tones.push(1200.0, spec.sync_time, ToneLabel::Sync);
 
-        // Repeat step 2 to step 8 for 256 lines.
-    }
+    // Step 7: The sync porch
+    tones.push(1500.0, spec.porch_time, ToneLabel::Porch);
 
-    result
+    // Step 8: The red scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[third.index()],
+            pixel_scan_time,
+            third,
+        )
+    });
 }
@@ -0,0 +1,60 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use std::f32::consts::PI;
+
+///
+/// A Hann window low pass filter for the frequency, so that the frequency glides between
+/// two tones instead of stepping, which keeps the spectrum narrow.
+///
+/// The frequency comes out delayed by half the length of the window.
+///
+pub(crate) struct ShapingFilter {
+    /// The weights of the window, adding up to 1.
+    taps: Vec<f32>,
+    /// The latest frequencies as a ring buffer, the oldest at `position`.
+    history: Vec<f32>,
+    position: usize,
+    /// Whether there is no frequency since the start or the last reset.
+    empty: bool,
+}
+
+impl ShapingFilter {
+    /// The window is `length` samples long, at least one.
+    pub(crate) fn new(length: usize) -> Self {
+        let length = length.max(1);
+        let taps: Vec<f32> = (1..=length)
+            .map(|n| (PI * n as f32 / (length + 1) as f32).sin().powi(2))
+            .collect();
+        let sum: f32 = taps.iter().sum();
+        Self {
+            taps: taps.iter().map(|tap| tap / sum).collect(),
+            history: vec![0.0; length],
+            position: 0,
+            empty: true,
+        }
+    }
+
+    /// Forget the previous frequencies, the next tone starts without gliding.
+    pub(crate) fn reset(&mut self) {
+        self.empty = true;
+    }
+
+    /// Add the frequency of the next sample, return the shaped frequency of it.
+    pub(crate) fn run(&mut self, frequency: f32) -> f32 {
+        if self.empty {
+            self.history.fill(frequency);
+            self.empty = false;
+        }
+        self.history[self.position] = frequency;
+        self.position = (self.position + 1) % self.history.len();
+
+        let (newer, older) = self.history.split_at(self.position);
+        older
+            .iter()
+            .chain(newer)
+            .zip(&self.taps)
+            .map(|(frequency, tap)| frequency * tap)
+            .sum()
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use crate::{
+    ColorModel, EncodeError, EncoderConfig, Family, Mode, ModeSpec, Modulation, Sample,
+    header::generate_header, martin::encode_line_in_martin, pasokon::encode_line_in_pasokon,
+    pd::encode_line_in_pd, robot_bw::encode_line_in_robot_bw, robot36::encode_line_in_robot36,
+    robot72::encode_line_in_robot72, sample_generator::SampleGenerator,
+    schottie::encode_line_in_schottie, sstv_image::SSTVImage, tone::ToneSequence,
+    warsse_sc2::encode_line_in_warsse_sc2,
+};
+
+/// Longest silence generated at once, in ms.
+const SILENCE_CHUNK_TIME: f64 = 100.0;
+
+/// What the encoder is sending.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    /// Silence before the header, with the time left in ms.
+    LeadingSilence(f64),
+    Header,
+    /// The line of the mode, a PD line carries two lines of the picture.
+    Line(usize),
+    /// Silence after the picture, with the time left in ms.
+    TrailingSilence(f64),
+    Done,
+}
+
+///
+/// Generate the pcm data of a picture on demand.
+///
+/// Only one line is generated at a time, so the memory stays the same however long
+/// the transmission is. Use it as an `Iterator<Item = S>`, or fill a buffer with
+/// [`SSTVEncoder::fill`], for example inside a sound card callback.
+///
+/// The samples are in the format `S`, which is 16 bit by default.
+///
+pub struct SSTVEncoder<S: Sample = i16> {
+    ctx: SampleGenerator,
+    /// The picture resized for the mode.
+    image: SSTVImage,
+    spec: &'static ModeSpec,
+    vis_header: bool,
+    trailing_silence: f64,
+    stage: Stage,
+    /// Samples of the current stage.
+    buffer: Vec<S>,
+    /// Index of the next sample inside the buffer.
+    position: usize,
+}
+
+impl<S: Sample> SSTVEncoder<S> {
+    /// Will return an error if the image is empty, or the config is invalid.
+    pub fn new(
+        config: &EncoderConfig,
+        image: &SSTVImage,
+        mode: &Mode,
+    ) -> Result<Self, EncodeError> {
+        config.validate()?;
+        if image.get_width() == 0 || image.get_height() == 0 {
+            return Err(EncodeError::EmptyImage);
+        }
+
+        let spec = mode.spec();
+        let mut ctx = SampleGenerator::from_config(config);
+        if config.modulation() == Modulation::Shaped {
+            // Glide over the shortest pixel, so a pixel still reaches its own frequency.
+            let shortest_pixel_time = spec
+                .scan_order
+                .iter()
+                .map(|channel| spec.scan_time(*channel))
+                .fold(f64::MAX, f64::min)
+                / spec.width as f64;
+            ctx.shape_frequency(shortest_pixel_time);
+        }
+        Ok(Self {
+            ctx,
+            image: image.resize_image(spec.width, spec.height),
+            spec,
+            vis_header: config.vis_header(),
+            trailing_silence: config.trailing_silence().as_secs_f64() * 1000.0,
+            stage: Stage::LeadingSilence(config.leading_silence().as_secs_f64() * 1000.0),
+            buffer: vec![],
+            position: 0,
+        })
+    }
+
+    /// Fill the buffer with the next samples, return how many are written.
+    ///
+    /// It is less than the length of the buffer only at the end of the transmission.
+    pub fn fill(&mut self, buffer: &mut [S]) -> usize {
+        let mut written = 0;
+        while written < buffer.len() {
+            if self.position >= self.buffer.len() && !self.next_stage() {
+                break;
+            }
+            let count = (self.buffer.len() - self.position).min(buffer.len() - written);
+            buffer[written..written + count]
+                .copy_from_slice(&self.buffer[self.position..self.position + count]);
+            self.position += count;
+            written += count;
+        }
+        written
+    }
+
+    /// Generate all the samples left at once.
+    pub(crate) fn render_samples(mut self) -> Vec<S> {
+        let mut samples = self.buffer.split_off(self.position);
+        while let Some(tones) = self.next_tones() {
+            self.ctx.render(&tones, &mut samples);
+        }
+        samples
+    }
+
+    /// Generate the samples of the next stage into the buffer.
+    ///
+    /// Return false if the transmission is over.
+    fn next_stage(&mut self) -> bool {
+        let Some(tones) = self.next_tones() else {
+            return false;
+        };
+        self.buffer.clear();
+        self.ctx.render(&tones, &mut self.buffer);
+        self.position = 0;
+        true
+    }
+
+    /// Move to the next stage and return its tones, None if the transmission is over.
+    fn next_tones(&mut self) -> Option<ToneSequence> {
+        let line_count = self.spec.line_count();
+        let mut tones = ToneSequence::new();
+        self.stage = match self.stage {
+            Stage::LeadingSilence(left) if left > 0.0 => {
+                let time = left.min(SILENCE_CHUNK_TIME);
+                tones.push_silence(time);
+                Stage::LeadingSilence(left - time)
+            }
+            Stage::LeadingSilence(_) if self.vis_header => {
+                generate_header(&mut tones, self.spec);
+                Stage::Header
+            }
+            Stage::LeadingSilence(_) | Stage::Header => {
+                encode_line(&mut tones, &self.image, self.spec, 0);
+                Stage::Line(0)
+            }
+            Stage::Line(line) if line + 1 < line_count => {
+                encode_line(&mut tones, &self.image, self.spec, line + 1);
+                Stage::Line(line + 1)
+            }
+            Stage::Line(_) => Stage::TrailingSilence(self.trailing_silence),
+            Stage::TrailingSilence(left) if left > 0.0 => {
+                let time = left.min(SILENCE_CHUNK_TIME);
+                tones.push_silence(time);
+                Stage::TrailingSilence(left - time)
+            }
+            Stage::TrailingSilence(_) | Stage::Done => {
+                self.stage = Stage::Done;
+                return None;
+            }
+        };
+        Some(tones)
+    }
+}
+
+/// Add the tones of one line of the mode, the image is already resized for the mode.
+pub(crate) fn encode_line(
+    tones: &mut ToneSequence,
+    image: &SSTVImage,
+    spec: &ModeSpec,
+    line: usize,
+) {
+    match spec.family {
+        Family::Scottie => encode_line_in_schottie(tones, image, spec, line),
+        Family::Martin => encode_line_in_martin(tones, image, spec, line),
+        Family::Robot if spec.color_model == ColorModel::Monochrome => {
+            encode_line_in_robot_bw(tones, image, spec, line)
+        }
+        // R-Y and B-Y on alternate lines.
+        Family::Robot if spec.scan_order.len() == 2 => {
+            encode_line_in_robot36(tones, image, spec, line)
+        }
+        Family::Robot => encode_line_in_robot72(tones, image, spec, line),
+        Family::Wraase => encode_line_in_warsse_sc2(tones, image, spec, line),
+        Family::Pasokon => encode_line_in_pasokon(tones, image, spec, line),
+        Family::Pd => encode_line_in_pd(tones, image, spec, line),
+    }
+}
+
+impl<S: Sample> Iterator for SSTVEncoder<S> {
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        while self.position >= self.buffer.len() {
+            if !self.next_stage() {
+                return None;
+            }
+        }
+        self.position += 1;
+        Some(self.buffer[self.position - 1])
+    }
+}
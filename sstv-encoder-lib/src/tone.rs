@@ -0,0 +1,158 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use std::time::Duration;
+
+use crate::{
+    Channel, EncodeError, EncoderConfig, Modulation, Sample, sample_generator::SampleGenerator,
+};
+
+/// What a tone is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ToneLabel {
+    /// No signal, the next tone starts from the zero phase.
+    Silence,
+    /// 1900hz leader tone of the VIS header.
+    Leader,
+    /// 1200hz break between the two leader tones.
+    Break,
+    /// 1200hz start bit of the VIS code.
+    VisStart,
+    /// A bit of the VIS code, 1100hz is 1 and 1300hz is 0.
+    VisBit,
+    /// The even parity bit of the VIS code.
+    VisParity,
+    /// 1200hz stop bit of the VIS code.
+    VisStop,
+    /// 1200hz sync pulse.
+    Sync,
+    /// The porch after the sync pulse.
+    Porch,
+    /// The separator pulse between two scans.
+    Separator,
+    /// The porch after the separator pulse.
+    SeparatorPorch,
+    /// A pixel of the channel.
+    Scan(Channel),
+}
+
+/// A constant frequency over a duration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tone {
+    /// In hz, 0 for silence.
+    pub frequency: f32,
+    /// In ms.
+    pub duration: f64,
+    pub label: ToneLabel,
+}
+
+///
+/// The tones of a transmission, before being turned into pcm data.
+///
+/// It does not depend on the sample rate, so the timing of a mode could be checked
+/// without audio, and the same transmission could be rendered at several sample rates.
+/// With the `serde` feature, it could be serialized, for example into JSON.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ToneSequence {
+    tones: Vec<Tone>,
+}
+
+impl ToneSequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, frequency: f32, duration: f64, label: ToneLabel) {
+        self.tones.push(Tone {
+            frequency,
+            duration,
+            label,
+        });
+    }
+
+    pub fn push_silence(&mut self, duration: f64) {
+        self.push(0.0, duration, ToneLabel::Silence);
+    }
+
+    ///
+    /// Add the tone of a color signal strength.
+    ///
+    /// Note that the library uses 8 bit unsigned integer value to store the color signal
+    /// strength, and the range of the color frequency is [1500,2300].
+    ///
+    /// Refrence is at below:
+    ///
+    /// > Dayton Paper Appendix A: RGB Color Encoding
+    /// >
+    /// > SSTV systems use the frequency range of 1500-2300hz to represent
+    /// > the range of brightness values from pure black to pure white.
+    /// > Because of this, we must convert out 8-bit R,G,B values into the
+    /// > appropriate frequency.
+    /// >
+    /// > In this formula, the RGB value is stored in 8 bit non-signed integer.
+    /// >
+    /// > $$ f = 1500 + s_r * 3.1372549 $$
+    /// >
+    /// > Where $f$ is the frequency of the strength of the value $s_r$.
+    ///
+    /// > Dayton Paper Appendix B: YRyBy (YCrCb) Color Encoding
+    /// >
+    /// > Again, as with RGB encoding, these values can be converted to frequency, for
+    /// > SSTV transmission:
+    /// >
+    /// > $$ f = 1500 + v * 3.1372549 $$
+    /// >
+    /// > Where 'v' is the value of the Y, R-Y, or B-Y
+    ///
+    pub fn push_color(&mut self, strength: u8, duration: f64, channel: Channel) {
+        let frequency = 1500.0 + (strength as f32) * 800.0 / 255.0;
+        self.push(frequency, duration, ToneLabel::Scan(channel));
+    }
+
+    pub fn tones(&self) -> &[Tone] {
+        &self.tones
+    }
+
+    pub fn extend(&mut self, other: &ToneSequence) {
+        self.tones.extend_from_slice(&other.tones);
+    }
+
+    /// Time to send all the tones.
+    pub fn duration(&self) -> Duration {
+        let time: f64 = self.tones.iter().map(|tone| tone.duration).sum();
+        Duration::from_secs_f64(time / 1000.0)
+    }
+
+    /// The shortest pixel, which the shaped modulation glides over.
+    pub(crate) fn shortest_pixel_time(&self) -> Option<f64> {
+        self.tones
+            .iter()
+            .filter(|tone| matches!(tone.label, ToneLabel::Scan(_)))
+            .map(|tone| tone.duration)
+            .reduce(f64::min)
+    }
+
+    ///
+    /// Generate the pcm data in the sample format `S`, at the sample rate and with the
+    /// amplitude and the modulation of the config.
+    ///
+    /// The silence and the VIS header options of the config are ignored, since the tones
+    /// already tell what to send. Will return an error if the config is invalid.
+    ///
+    pub fn render<S: Sample>(&self, config: &EncoderConfig) -> Result<Vec<S>, EncodeError> {
+        config.validate()?;
+        let mut ctx = SampleGenerator::from_config(config);
+        if config.modulation() == Modulation::Shaped
+            && let Some(pixel_time) = self.shortest_pixel_time()
+        {
+            ctx.shape_frequency(pixel_time);
+        }
+        let mut samples = Vec::new();
+        ctx.render(self, &mut samples);
+        Ok(samples)
+    }
+}
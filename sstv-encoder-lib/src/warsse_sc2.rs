@@ -0,0 +1,71 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+use crate::{
+    ModeSpec,
+    sstv_image::SSTVImage,
+    tone::{ToneLabel, ToneSequence},
+};
+
+///
+/// WRASSE SC2 modes
+///
+/// VIS Code:
+///
+/// |Mode | Code in Decimal|
+/// |-----------------|-----|
+/// | WRASSE SC2-30   | 51  |
+/// | WRASSE SC2-60   | 59  |
+/// | WRASSE SC2-120  | 63  |
+/// | WRASSE SC2-180  | 55  |
+///
+/// Color Mode: RGB (1500-2300hz luminance range)
+/// Scan Sequence: Red, Green, Blue
+///
+/// Image Size: 320x256 (including 16-line header), 320x128 for SC2-30
+///
+/// The modes only differ in the pixel time, SC2-30 is SC2-60 with half of the lines.
+///
+pub(crate) fn encode_line_in_warsse_sc2(
+    tones: &mut ToneSequence,
+    image: &SSTVImage,
+    spec: &ModeSpec,
+    line: usize,
+) {
+    let pixel_scan_time = spec.pixel_time;
+    let [first, second, third] = [0, 1, 2].map(|i| spec.scan_order[i]);
+
+    let y = line;
+
+    // Step 1: The sync pulse
+    tones.push(1200.0, spec.sync_time, ToneLabel::Sync);
+
+    // Step 2: The sync porch
+    tones.push(1500.0, spec.porch_time, ToneLabel::Porch);
+
+    // Step 3: The red scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[first.index()],
+            pixel_scan_time,
+            first,
+        );
+    });
+
+    // Step 4: The green scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[second.index()],
+            pixel_scan_time,
+            second,
+        );
+    });
+    // Step 5: The blue scan
+    (0..image.get_width()).for_each(|x| {
+        tones.push_color(
+            image.get_rgb_pixel(x, y)[third.index()],
+            pixel_scan_time,
+            third,
+        );
+    });
+}
@@ -0,0 +1,35 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use sstv_encoder_lib::{EncoderConfig, Mode, encode_picture_into_pcm};
+
+use common::gradient;
+
+/// The largest i16 sample of the transmission at the amplitude.
+fn peak(amplitude_dbfs: f32, sine_table: bool) -> u16 {
+    let config = EncoderConfig::new(11025)
+        .with_amplitude_dbfs(amplitude_dbfs)
+        .with_sine_table(sine_table);
+    let samples = encode_picture_into_pcm::<i16>(&config, &gradient(), &Mode::Robot8Bw).unwrap();
+    samples
+        .iter()
+        .map(|sample| sample.unsigned_abs())
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn peak_follows_the_amplitude() {
+    // 32768 * 10^(-6 / 20) is 16422.5, and 32768 * 10^(-40 / 20) is 327.68.
+    for (amplitude_dbfs, expected) in [(0.0, 32767), (-6.0, 16422), (-40.0, 328)] {
+        for sine_table in [false, true] {
+            let peak = peak(amplitude_dbfs, sine_table);
+            assert!(
+                peak.abs_diff(expected) <= 1,
+                "{amplitude_dbfs} dBFS, sine table {sine_table}: peak {peak}"
+            );
+        }
+    }
+}
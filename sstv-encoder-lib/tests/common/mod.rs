@@ -0,0 +1,45 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+#![allow(dead_code)]
+
+use sstv_encoder_lib::sstv_image::SSTVImage;
+
+/// A flat middle gray, the same tones on every line.
+pub fn gray(width: usize, height: usize) -> SSTVImage {
+    SSTVImage::new(width, height, vec![[128; 3]; width * height]).unwrap()
+}
+
+/// Red rising from left to right and green from top to bottom, over a constant blue.
+pub fn gradient() -> SSTVImage {
+    let (width, height) = (320, 256);
+    let pixels = (0..width * height)
+        .map(|i| [(i % width * 255 / width) as u8, (i / width) as u8, 128])
+        .collect();
+    SSTVImage::new(width, height, pixels).unwrap()
+}
+
+/// Black and white columns, the widest spectrum a picture could have.
+pub fn black_and_white_columns() -> SSTVImage {
+    let (width, height) = (320, 256);
+    let pixels = (0..width * height)
+        .map(|i| if i % width % 2 == 0 { [0; 3] } else { [255; 3] })
+        .collect();
+    SSTVImage::new(width, height, pixels).unwrap()
+}
+
+/// Green and magenta columns, so every channel jumps between black and white at every
+/// pixel.
+pub fn green_and_magenta_columns() -> SSTVImage {
+    let (width, height) = (320, 256);
+    let pixels = (0..width * height)
+        .map(|i| {
+            if i % width % 2 == 0 {
+                [0, 255, 0]
+            } else {
+                [255, 0, 255]
+            }
+        })
+        .collect();
+    SSTVImage::new(width, height, pixels).unwrap()
+}
@@ -0,0 +1,75 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use sstv_encoder_lib::{
+    EncodeError, EncoderConfig, Mode, SSTVEncoder, ToneSequence, encode_picture_into_pcm,
+    encode_picture_into_tones, sstv_image::SSTVImage,
+};
+
+use common::gray;
+
+/// The error of encoding the picture into pcm data, and of the streaming encoder.
+fn encode_error(config: &EncoderConfig, image: &SSTVImage) -> EncodeError {
+    let error = encode_picture_into_pcm::<i16>(config, image, &Mode::Robot8Bw).unwrap_err();
+    let streaming = SSTVEncoder::<i16>::new(config, image, &Mode::Robot8Bw).err();
+    assert_eq!(streaming.as_ref(), Some(&error));
+    error
+}
+
+#[test]
+fn empty_image() {
+    let config = EncoderConfig::new(11025);
+    for (width, height) in [(0, 0), (0, 16), (16, 0)] {
+        let image = SSTVImage::new(width, height, vec![]).unwrap();
+        assert_eq!(encode_error(&config, &image), EncodeError::EmptyImage);
+        assert_eq!(
+            encode_picture_into_tones(&config, &image, &Mode::Robot8Bw),
+            Err(EncodeError::EmptyImage)
+        );
+    }
+}
+
+#[test]
+fn sample_rate_too_low_for_the_white_level() {
+    // 2300hz needs more than 4600hz.
+    for sample_rate in [0, 4000, 4600] {
+        let config = EncoderConfig::new(sample_rate);
+        assert_eq!(
+            encode_error(&config, &gray(16, 16)),
+            EncodeError::InvalidSampleRate(sample_rate)
+        );
+    }
+    let config = EncoderConfig::new(4601);
+    assert!(encode_picture_into_pcm::<i16>(&config, &gray(16, 16), &Mode::Robot8Bw).is_ok());
+}
+
+#[test]
+fn amplitude_above_the_full_scale() {
+    for amplitude_dbfs in [0.5, f32::INFINITY] {
+        let config = EncoderConfig::new(11025).with_amplitude_dbfs(amplitude_dbfs);
+        assert_eq!(
+            encode_error(&config, &gray(16, 16)),
+            EncodeError::InvalidAmplitude(amplitude_dbfs)
+        );
+    }
+    let config = EncoderConfig::new(11025).with_amplitude_dbfs(f32::NAN);
+    assert!(matches!(
+        encode_picture_into_pcm::<i16>(&config, &gray(16, 16), &Mode::Robot8Bw),
+        Err(EncodeError::InvalidAmplitude(amplitude_dbfs)) if amplitude_dbfs.is_nan()
+    ));
+}
+
+#[test]
+fn tones_are_rendered_with_a_valid_config_only() {
+    let tones = ToneSequence::new();
+    assert_eq!(
+        tones.render::<i16>(&EncoderConfig::new(4000)),
+        Err(EncodeError::InvalidSampleRate(4000))
+    );
+    assert_eq!(
+        tones.render::<i16>(&EncoderConfig::new(11025).with_amplitude_dbfs(1.0)),
+        Err(EncodeError::InvalidAmplitude(1.0))
+    );
+}
@@ -0,0 +1,68 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use std::f64::consts::PI;
+
+use sstv_encoder_lib::{EncoderConfig, Mode, Modulation, SSTVEncoder};
+
+use common::black_and_white_columns;
+
+const SAMPLE_RATE: u32 = 11025;
+
+/// Samples in the spectrum.
+const WINDOW_SIZE: usize = 4096;
+
+/// Width in hz of the band holding 99% of the power, from the middle of the picture.
+fn occupied_bandwidth(modulation: Modulation) -> f64 {
+    let config = EncoderConfig::new(SAMPLE_RATE).with_modulation(modulation);
+    let samples: Vec<f32> = SSTVEncoder::new(&config, &black_and_white_columns(), &Mode::Pd50)
+        .unwrap()
+        .collect();
+    let start = samples.len() / 2;
+    let window: Vec<f64> = samples[start..start + WINDOW_SIZE]
+        .iter()
+        .enumerate()
+        .map(|(n, sample)| *sample as f64 * (PI * n as f64 / WINDOW_SIZE as f64).sin().powi(2))
+        .collect();
+
+    let power: Vec<f64> = (0..WINDOW_SIZE / 2)
+        .map(|k| {
+            let (re, im) = window
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(re, im), (n, x)| {
+                    let angle = 2.0 * PI * ((k * n) % WINDOW_SIZE) as f64 / WINDOW_SIZE as f64;
+                    (re + x * angle.cos(), im - x * angle.sin())
+                });
+            re * re + im * im
+        })
+        .collect();
+
+    let total: f64 = power.iter().sum();
+    let mut cumulative = 0.0;
+    let (mut low, mut high) = (0, 0);
+    for (k, p) in power.iter().enumerate() {
+        cumulative += p;
+        if cumulative < total * 0.005 {
+            low = k + 1;
+        }
+        if cumulative < total * 0.995 {
+            high = k + 1;
+        }
+    }
+    (high - low) as f64 * SAMPLE_RATE as f64 / WINDOW_SIZE as f64
+}
+
+#[test]
+fn shaped_modulation_narrows_the_spectrum() {
+    let stepped = occupied_bandwidth(Modulation::Stepped);
+    let shaped = occupied_bandwidth(Modulation::Shaped);
+    assert!(
+        shaped < stepped * 0.8,
+        "shaped {shaped:.0}hz is not much narrower than stepped {stepped:.0}hz"
+    );
+    // 1500hz to 2300hz, plus the sidebands of the pixels.
+    assert!(shaped < 2800.0, "shaped {shaped:.0}hz");
+}
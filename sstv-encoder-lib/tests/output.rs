@@ -0,0 +1,184 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use std::{io::Cursor, time::Duration};
+
+use sstv_encoder_lib::{
+    EncoderConfig, I24, Mode, OutputFormat, Sample, encode_picture_into_seekable_writer,
+    encode_picture_into_writer,
+};
+
+use common::gray;
+
+const SAMPLE_RATE: u32 = 8000;
+
+const MODE: Mode = Mode::Robot8Bw;
+
+fn encode<S: Sample>(config: &EncoderConfig, format: OutputFormat) -> Vec<u8> {
+    let mut bytes = vec![];
+    let image = gray(160, 120);
+    encode_picture_into_writer::<S, _>(config, &image, &MODE, format, &mut bytes).unwrap();
+    bytes
+}
+
+fn encode_seekable<S: Sample>(config: &EncoderConfig, format: OutputFormat) -> Vec<u8> {
+    let mut cursor = Cursor::new(vec![]);
+    let image = gray(160, 120);
+    encode_picture_into_seekable_writer::<S, _>(config, &image, &MODE, format, &mut cursor)
+        .unwrap();
+    cursor.into_inner()
+}
+
+fn le_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn le_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn wav_header() {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    let raw = encode::<i16>(&config, OutputFormat::Raw);
+    let wav = encode::<i16>(&config, OutputFormat::Wav);
+
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(le_u32(&wav, 4) as usize, wav.len() - 8);
+    assert_eq!(&wav[8..16], b"WAVEfmt ");
+    assert_eq!(le_u32(&wav, 16), 16);
+    // Integer pcm, mono.
+    assert_eq!(le_u16(&wav, 20), 1);
+    assert_eq!(le_u16(&wav, 22), 1);
+    assert_eq!(le_u32(&wav, 24), SAMPLE_RATE);
+    assert_eq!(le_u32(&wav, 28), SAMPLE_RATE * 2);
+    assert_eq!(le_u16(&wav, 32), 2);
+    assert_eq!(le_u16(&wav, 34), 16);
+    assert_eq!(&wav[36..40], b"data");
+    assert_eq!(le_u32(&wav, 40) as usize, raw.len());
+    assert_eq!(wav[44..], raw);
+
+    assert_eq!(encode_seekable::<i16>(&config, OutputFormat::Wav), wav);
+}
+
+#[test]
+fn float_wav_header() {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    let raw = encode::<f32>(&config, OutputFormat::Raw);
+    let wav = encode::<f32>(&config, OutputFormat::Wav);
+
+    assert_eq!(le_u32(&wav, 4) as usize, wav.len() - 8);
+    // The fmt chunk has an empty extension.
+    assert_eq!(le_u32(&wav, 16), 18);
+    assert_eq!(le_u16(&wav, 20), 3);
+    assert_eq!(le_u32(&wav, 28), SAMPLE_RATE * 4);
+    assert_eq!(le_u16(&wav, 32), 4);
+    assert_eq!(le_u16(&wav, 34), 32);
+    assert_eq!(le_u16(&wav, 36), 0);
+    // The fact chunk holds the sample count.
+    assert_eq!(&wav[38..42], b"fact");
+    assert_eq!(le_u32(&wav, 42), 4);
+    assert_eq!(le_u32(&wav, 46) as usize, raw.len() / 4);
+    assert_eq!(&wav[50..54], b"data");
+    assert_eq!(le_u32(&wav, 54) as usize, raw.len());
+    assert_eq!(wav[58..], raw);
+
+    assert_eq!(encode_seekable::<f32>(&config, OutputFormat::Wav), wav);
+}
+
+#[test]
+fn extensible_wav_header() {
+    // Without the VIS header, and with the silence, the samples are counted beforehand all
+    // the same.
+    let config = EncoderConfig::new(SAMPLE_RATE)
+        .with_vis_header(false)
+        .with_leading_silence(Duration::from_millis(3))
+        .with_trailing_silence(Duration::from_millis(250));
+    let raw = encode::<I24>(&config, OutputFormat::Raw);
+    let wav = encode::<I24>(&config, OutputFormat::Wav);
+
+    assert_eq!(le_u32(&wav, 4) as usize, wav.len() - 8);
+    assert_eq!(le_u32(&wav, 16), 40);
+    assert_eq!(le_u16(&wav, 20), 0xfffe);
+    assert_eq!(le_u16(&wav, 22), 1);
+    assert_eq!(le_u32(&wav, 28), SAMPLE_RATE * 3);
+    assert_eq!(le_u16(&wav, 32), 3);
+    assert_eq!(le_u16(&wav, 34), 24);
+    // The extension: its size, the valid bits, the front center speaker and the pcm GUID.
+    assert_eq!(le_u16(&wav, 36), 22);
+    assert_eq!(le_u16(&wav, 38), 24);
+    assert_eq!(le_u32(&wav, 40), 4);
+    assert_eq!(
+        wav[44..60],
+        [
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38,
+            0x9b, 0x71
+        ]
+    );
+    assert_eq!(&wav[60..64], b"data");
+    assert_eq!(le_u32(&wav, 64) as usize, raw.len());
+    assert_eq!(wav[68..68 + raw.len()], raw);
+    assert_eq!(wav.len(), 68 + raw.len() + raw.len() % 2);
+
+    assert_eq!(encode_seekable::<I24>(&config, OutputFormat::Wav), wav);
+}
+
+#[test]
+fn odd_wav_data_is_padded() {
+    // A leading silence of a few ms gives an odd count of u8 samples.
+    let config = (0..4)
+        .map(|ms| EncoderConfig::new(SAMPLE_RATE).with_leading_silence(Duration::from_millis(ms)))
+        .find(|config| encode::<u8>(config, OutputFormat::Raw).len() % 2 == 1)
+        .expect("an odd length");
+    let raw = encode::<u8>(&config, OutputFormat::Raw);
+    let wav = encode::<u8>(&config, OutputFormat::Wav);
+
+    assert_eq!(le_u32(&wav, 40) as usize, raw.len());
+    assert_eq!(wav.len(), 44 + raw.len() + 1);
+    assert_eq!(wav.last(), Some(&0));
+    assert_eq!(le_u32(&wav, 4) as usize, wav.len() - 8);
+    assert_eq!(wav[44..44 + raw.len()], raw);
+
+    assert_eq!(encode_seekable::<u8>(&config, OutputFormat::Wav), wav);
+}
+
+#[test]
+fn au_header() {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    let raw = encode::<i16>(&config, OutputFormat::Raw);
+    let au = encode::<i16>(&config, OutputFormat::Au);
+
+    assert_eq!(&au[0..4], b".snd");
+    assert_eq!(be_u32(&au, 4), 24);
+    // The length is unknown without seeking.
+    assert_eq!(be_u32(&au, 8), 0xffffffff);
+    // 16 bit linear pcm, mono.
+    assert_eq!(be_u32(&au, 12), 3);
+    assert_eq!(be_u32(&au, 16), SAMPLE_RATE);
+    assert_eq!(be_u32(&au, 20), 1);
+    // Big endian samples.
+    let swapped: Vec<u8> = raw.chunks(2).flat_map(|pair| [pair[1], pair[0]]).collect();
+    assert_eq!(au[24..], swapped);
+
+    let seekable = encode_seekable::<i16>(&config, OutputFormat::Au);
+    assert_eq!(be_u32(&seekable, 8) as usize, raw.len());
+    assert_eq!(seekable[24..], au[24..]);
+}
+
+#[test]
+fn au_u8_samples_are_signed() {
+    let config = EncoderConfig::new(SAMPLE_RATE);
+    let raw = encode::<u8>(&config, OutputFormat::Raw);
+    let au = encode::<u8>(&config, OutputFormat::Au);
+
+    // 8 bit linear pcm.
+    assert_eq!(be_u32(&au, 12), 2);
+    let signed: Vec<u8> = raw.iter().map(|sample| sample ^ 0x80).collect();
+    assert_eq!(au[24..], signed);
+}
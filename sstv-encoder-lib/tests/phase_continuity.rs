@@ -0,0 +1,41 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use sstv_encoder_lib::{EncoderConfig, Mode, SSTVEncoder};
+
+use common::green_and_magenta_columns;
+
+/// At this sample rate a continuous 2300hz tone moves less than 0.16 between two
+/// samples, while a phase jump moves up to 2.
+const SAMPLE_RATE: u32 = 96000;
+
+fn assert_continuous(config: &EncoderConfig, mode: Mode) {
+    let max_step = 2.0 * (std::f32::consts::PI * 2300.0 / SAMPLE_RATE as f32).sin() + 1e-4;
+    let samples: Vec<f32> = SSTVEncoder::new(config, &green_and_magenta_columns(), &mode)
+        .unwrap()
+        .collect();
+    assert!(samples.len() > 1000);
+    for (index, pair) in samples.windows(2).enumerate() {
+        let step = (pair[1] - pair[0]).abs();
+        assert!(
+            step <= max_step,
+            "{mode}: jump of {step} at sample {index}, at most {max_step}"
+        );
+    }
+}
+
+#[test]
+fn phase_is_continuous_across_tones() {
+    for mode in [Mode::Martin2, Mode::Pd50] {
+        assert_continuous(&EncoderConfig::new(SAMPLE_RATE), mode);
+    }
+}
+
+#[test]
+fn phase_is_continuous_with_sine_table() {
+    for mode in [Mode::Scottie2, Mode::Robot36] {
+        assert_continuous(&EncoderConfig::new(SAMPLE_RATE).with_sine_table(true), mode);
+    }
+}
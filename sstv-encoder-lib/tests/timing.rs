@@ -0,0 +1,110 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use std::time::Duration;
+
+use sstv_encoder_lib::{EncoderConfig, Mode, SSTVEncoder, ToneLabel, encode_picture_into_tones};
+
+use common::gray;
+
+/// Line times given by the Dayton paper and the SSTV Handbook, in ms, to check the
+/// mode table against.
+const NOMINAL_LINE_TIMES: [(Mode, f64); 11] = [
+    (Mode::Martin1, 446.446),
+    (Mode::Martin2, 226.798),
+    (Mode::Robot36, 150.0),
+    (Mode::Robot72, 300.0),
+    (Mode::Pd50, 388.16),
+    (Mode::Pd90, 703.04),
+    (Mode::Pd120, 508.48),
+    (Mode::Pd160, 804.416),
+    (Mode::Pd180, 754.24),
+    (Mode::Pd240, 1000.0),
+    (Mode::Pd290, 937.28),
+];
+
+/// Leader tone, break, leader tone and the ten VIS bits, in ms.
+const HEADER_TIME: f64 = 910.0;
+
+/// Samples sent for the mode, counted a buffer at a time.
+fn sent_samples(config: &EncoderConfig, mode: Mode) -> usize {
+    let mut encoder: SSTVEncoder<i16> = SSTVEncoder::new(config, &gray(320, 256), &mode).unwrap();
+    let mut buffer = vec![0; 4096];
+    let mut count = 0;
+    loop {
+        match encoder.fill(&mut buffer) {
+            0 => return count,
+            written => count += written,
+        }
+    }
+}
+
+fn assert_nominal_length(config: &EncoderConfig, mode: Mode) {
+    let expected = mode.sample_count(config.sample_rate());
+    let sent = sent_samples(config, mode);
+    assert_eq!(sent, expected, "{mode} at {}hz", config.sample_rate());
+}
+
+#[test]
+fn every_mode_matches_its_duration() {
+    for sample_rate in [8000, 11025] {
+        for mode in Mode::all() {
+            assert_nominal_length(&EncoderConfig::new(sample_rate), mode);
+        }
+    }
+}
+
+#[test]
+fn long_modes_do_not_drift_at_high_sample_rates() {
+    assert_nominal_length(&EncoderConfig::new(44100), Mode::P7);
+    assert_nominal_length(&EncoderConfig::new(48000), Mode::Pd290);
+}
+
+#[test]
+fn lines_start_at_their_nominal_time() {
+    let config = EncoderConfig::new(11025);
+    for (mode, line_time) in NOMINAL_LINE_TIMES {
+        let tones = encode_picture_into_tones(&config, &gray(320, 256), &mode).unwrap();
+        let mut elapsed = 0.0;
+        let mut line = 0;
+        for tone in tones.tones() {
+            if tone.label == ToneLabel::Sync {
+                let expected = HEADER_TIME + line_time * line as f64;
+                // A sample is 0.0104ms at 96khz.
+                assert!(
+                    (elapsed - expected).abs() < 1e-4,
+                    "{mode} line {line}: starts at {elapsed}ms, expected {expected}ms"
+                );
+                line += 1;
+            }
+            elapsed += tone.duration;
+        }
+        assert_eq!(line, mode.spec().line_count(), "{mode}");
+    }
+}
+
+#[test]
+fn ends_halfway_between_two_samples_are_rounded_up() {
+    // 72910ms, 24910ms and 36910ms are 1607665.5, 549265.5 and 813865.5 samples.
+    for mode in [Mode::Robot72, Mode::Robot24Bw, Mode::Robot36Bw] {
+        assert_nominal_length(&EncoderConfig::new(22050), mode);
+    }
+}
+
+#[test]
+fn silence_keeps_the_timing() {
+    // 1/3 ms does not fit a whole number of samples at any of the sample rates.
+    let silence = Duration::from_nanos(333_333);
+    for sample_rate in [8000, 11025, 22050] {
+        let config = EncoderConfig::new(sample_rate)
+            .with_leading_silence(silence * 900)
+            .with_trailing_silence(silence * 300);
+        // The end of the transmission is still the sample nearest to its time.
+        let time = Mode::Robot36.duration() + config.leading_silence() + config.trailing_silence();
+        let expected = (time.as_secs_f64() * sample_rate as f64).round() as usize;
+        let sent = sent_samples(&config, Mode::Robot36);
+        assert_eq!(sent, expected, "{sample_rate}hz");
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2025 BenderBlog Rodriguez and Contributors.
+// SPDX-License-Identifier: 0BSD
+
+mod common;
+
+use std::time::Duration;
+
+use sstv_encoder_lib::{
+    Channel, EncoderConfig, Mode, SSTVEncoder, ToneLabel, encode_picture_into_tones,
+};
+
+use common::gradient;
+
+#[test]
+fn every_mode_lasts_its_duration() {
+    for mode in Mode::all() {
+        let tones =
+            encode_picture_into_tones(&EncoderConfig::default(), &gradient(), &mode).unwrap();
+        let difference = tones.duration().abs_diff(mode.duration());
+        assert!(
+            difference < Duration::from_micros(1),
+            "{mode}: {:?}, expected {:?}",
+            tones.duration(),
+            mode.duration()
+        );
+    }
+}
+
+#[test]
+fn header_carries_the_vis_code() {
+    let tones =
+        encode_picture_into_tones(&EncoderConfig::default(), &gradient(), &Mode::Martin1).unwrap();
+    let labels: Vec<ToneLabel> = tones.tones()[..13].iter().map(|tone| tone.label).collect();
+    assert_eq!(
+        labels,
+        [
+            [
+                ToneLabel::Leader,
+                ToneLabel::Break,
+                ToneLabel::Leader,
+                ToneLabel::VisStart
+            ]
+            .as_slice(),
+            &[ToneLabel::VisBit; 7],
+            &[ToneLabel::VisParity, ToneLabel::VisStop],
+        ]
+        .concat()
+    );
+
+    // 44 is 0101100 from the least significant bit, with an odd count of ones.
+    let bits: Vec<f32> = tones.tones()[4..12]
+        .iter()
+        .map(|tone| tone.frequency)
+        .collect();
+    assert_eq!(
+        bits,
+        [
+            1300.0, 1300.0, 1100.0, 1100.0, 1300.0, 1100.0, 1300.0, 1100.0
+        ]
+    );
+}
+
+#[test]
+fn martin_line_follows_the_scan_order() {
+    let config = EncoderConfig::default().with_vis_header(false);
+    let tones = encode_picture_into_tones(&config, &gradient(), &Mode::Martin1).unwrap();
+    let mut labels: Vec<ToneLabel> = tones.tones().iter().map(|tone| tone.label).collect();
+    labels.dedup();
+    assert_eq!(
+        labels[..8],
+        [
+            ToneLabel::Sync,
+            ToneLabel::Porch,
+            ToneLabel::Scan(Channel::Green),
+            ToneLabel::Separator,
+            ToneLabel::Scan(Channel::Blue),
+            ToneLabel::Separator,
+            ToneLabel::Scan(Channel::Red),
+            ToneLabel::Separator,
+        ]
+    );
+}
+
+#[test]
+fn rendering_matches_the_streaming_encoder() {
+    let config = EncoderConfig::default().with_leading_silence(Duration::from_millis(30));
+    let tones = encode_picture_into_tones(&config, &gradient(), &Mode::Robot36).unwrap();
+    for sample_rate in [8000, 11025] {
+        let config = config.clone().with_sample_rate(sample_rate);
+        let streamed: Vec<i16> = SSTVEncoder::new(&config, &gradient(), &Mode::Robot36)
+            .unwrap()
+            .collect();
+        assert_eq!(tones.render::<i16>(&config).unwrap(), streamed);
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn tones_round_trip_through_json() {
+    let tones =
+        encode_picture_into_tones(&EncoderConfig::default(), &gradient(), &Mode::Robot36).unwrap();
+    let json = serde_json::to_string(&tones).unwrap();
+    assert!(
+        json.starts_with(r#"{"tones":[{"frequency":1900.0,"duration":300.0,"label":"Leader"}"#)
+    );
+    assert_eq!(
+        serde_json::from_str::<sstv_encoder_lib::ToneSequence>(&json).unwrap(),
+        tones
+    );
+}